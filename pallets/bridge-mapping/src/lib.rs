@@ -7,15 +7,24 @@
 //! For example, Eth may be an abstract resource, with instances of it being the native token on
 //! Ethereum, and also a derivative token on some bridged Substrate blockchain.
 //!
-//! Resources are set and removed by an Admin account or by root.
+//! A resource may also represent a fungible asset, e.g. an ERC-20 token, that is held locally as
+//! a local asset id.
+//!
+//! Resources are set and removed by an Admin account or by root. Every resource can carry
+//! metadata describing its kind and the chains it may be transferred to, and can be disabled to
+//! stop transfers of it.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::FullCodec;
-use sp_runtime::traits::{Member, BadOrigin};
+use codec::{Decode, Encode, FullCodec};
+use sp_runtime::{traits::{Member, BadOrigin}, RuntimeDebug};
+use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::Serialize;
 use frame_system::ensure_root;
 use frame_support::{
-    decl_module, decl_storage,
+    decl_error, decl_event, decl_module, decl_storage,
     dispatch::DispatchResult,
+    ensure,
     traits::{Get, EnsureOrigin}};
 
 #[cfg(test)]
@@ -24,7 +33,45 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// The kind of asset a resource represents
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+pub enum ResourceKind {
+    /// The native token of this chain
+    Native,
+    /// Non-fungible tokens of a registry
+    Nft,
+    /// A fungible token, e.g. an ERC-20 token
+    Fungible,
+    /// Anything else, e.g. arbitrary calls
+    Generic,
+    /// Non-fungible tokens of another chain, minted into the mapped registry on their first
+    /// receipt
+    ForeignNft,
+}
+
+impl Default for ResourceKind {
+    fn default() -> Self {
+        ResourceKind::Generic
+    }
+}
+
+/// Describes a resource and where it may be transferred to
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ResourceMetadata {
+    pub kind: ResourceKind,
+    /// Number of decimals of the resource's smallest unit, zero for non-fungible resources
+    pub decimals: u8,
+    /// Chain ids the resource may be transferred to. Any chain is allowed if empty.
+    pub allowed_chains: Vec<u8>,
+    /// Transfers of a disabled resource are rejected
+    pub enabled: bool,
+}
+
 pub trait Trait: frame_system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     /// In order to provide generality, we need some way to associate some action on a source chain
     /// to some action on a destination chain. This may express tokenX on chain A is equivalent to
     /// tokenY on chain B, or to simply associate that some action performed on chain A should
@@ -34,6 +81,8 @@ pub trait Trait: frame_system::Trait {
     /// A local mapping of a resource id. Represents anything that a resource id might map to. On
     /// Ethereum, this may be a contract address for transferring assets.
     type Address: Member + Default + FullCodec + Into<[u8; 32]> + From<[u8; 32]>;
+    /// Identifier of a local fungible asset a resource id may map to.
+    type AssetId: Member + Default + FullCodec;
     /// Admin is able to set/remove resource mappings.
     type AdminOrigin: EnsureOrigin<Self::Origin>;
 }
@@ -46,14 +95,56 @@ decl_storage! {
         /// Maps a chain-specific address to a resource id. A mapping in [ResourceToAddress] will
         /// always correspond to a mapping here. Resources and addresses are 1 to 1.
         AddressToResource get(fn name_of): map hasher(blake2_128_concat) T::Address => Option<T::ResourceId>;
+        /// Maps an abstract resource id to a local fungible asset
+        ResourceToAsset get(fn asset_of): map hasher(blake2_128_concat) T::ResourceId => Option<T::AssetId>;
+        /// Maps a local fungible asset to a resource id. Resources and assets are 1 to 1.
+        AssetToResource get(fn resource_of_asset): map hasher(blake2_128_concat) T::AssetId => Option<T::ResourceId>;
+        /// Metadata of a resource. Resources without metadata are enabled for any chain.
+        Metadata get(fn metadata_of): map hasher(blake2_128_concat) T::ResourceId => Option<ResourceMetadata>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where
+        <T as Trait>::ResourceId,
+        <T as Trait>::Address,
+        <T as Trait>::AssetId,
+    {
+        /// The resource was mapped to the address
+        ResourceSet(ResourceId, Address),
+        /// The mapping of the resource to its address was removed
+        ResourceRemoved(ResourceId),
+        /// The resource was mapped to the local fungible asset
+        AssetSet(ResourceId, AssetId),
+        /// The mapping of the resource to its local fungible asset was removed
+        AssetRemoved(ResourceId),
+        /// The metadata of the resource was set
+        MetadataSet(ResourceId, ResourceMetadata),
+        /// The resource was enabled (true) or disabled (false)
+        ResourceEnabled(ResourceId, bool),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// The address is already mapped to another resource
+        AddressInUse,
+        /// The asset is already mapped to another resource
+        AssetInUse,
+        /// The resource has no metadata
+        NoMetadata,
     }
 }
 
 decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-        /// Set a resource mapping in the [Names]. Existing keys will be overwritten.
+        type Error = Error<T>;
+        fn deposit_event() = default;
+
+        /// Set a resource mapping in the [Names]. An existing address of the resource is replaced,
+        /// but an address that is mapped to another resource is rejected.
         /// The caller must be the owner of the `rid` ResourceId.
-        #[weight = T::DbWeight::get().reads_writes(0,2) + 100_000]
+        #[weight = T::DbWeight::get().reads_writes(2,3) + 100_000]
         pub fn set(origin,
                    rid: T::ResourceId,
                    local_addr: T::Address,
@@ -61,11 +152,14 @@ decl_module! {
             Self::ensure_admin_or_root(origin)?;
 
             // Call internal
-            Self::set_resource(rid, local_addr);
+            Self::set_resource(rid.clone(), local_addr.clone())?;
+
+            Self::deposit_event(RawEvent::ResourceSet(rid, local_addr));
             Ok(())
         }
 
-        #[weight = T::DbWeight::get().reads_writes(1,2) + 100_000]
+        /// Remove a resource mapping along with the resource's metadata.
+        #[weight = T::DbWeight::get().reads_writes(1,3) + 100_000]
         pub fn remove(origin,
                       rid: T::ResourceId,
         ) -> DispatchResult {
@@ -73,6 +167,67 @@ decl_module! {
 
             // Call internal
             Self::remove_resource(&rid);
+
+            Self::deposit_event(RawEvent::ResourceRemoved(rid));
+            Ok(())
+        }
+
+        /// Set a mapping of a resource id to a local fungible asset. An existing asset of the
+        /// resource is replaced, but an asset that is mapped to another resource is rejected.
+        #[weight = T::DbWeight::get().reads_writes(2,3) + 100_000]
+        pub fn set_asset(origin,
+                         rid: T::ResourceId,
+                         asset_id: T::AssetId,
+        ) -> DispatchResult {
+            Self::ensure_admin_or_root(origin)?;
+
+            // Call internal
+            Self::set_asset_resource(rid.clone(), asset_id.clone())?;
+
+            Self::deposit_event(RawEvent::AssetSet(rid, asset_id));
+            Ok(())
+        }
+
+        #[weight = T::DbWeight::get().reads_writes(1,2) + 100_000]
+        pub fn remove_asset(origin,
+                            rid: T::ResourceId,
+        ) -> DispatchResult {
+            Self::ensure_admin_or_root(origin)?;
+
+            // Call internal
+            Self::remove_asset_resource(&rid);
+
+            Self::deposit_event(RawEvent::AssetRemoved(rid));
+            Ok(())
+        }
+
+        /// Set the metadata of a resource.
+        #[weight = T::DbWeight::get().reads_writes(0,1) + 100_000]
+        pub fn set_metadata(origin,
+                            rid: T::ResourceId,
+                            metadata: ResourceMetadata,
+        ) -> DispatchResult {
+            Self::ensure_admin_or_root(origin)?;
+            Metadata::<T>::insert(&rid, &metadata);
+
+            Self::deposit_event(RawEvent::MetadataSet(rid, metadata));
+            Ok(())
+        }
+
+        /// Enable or disable transfers of a resource with metadata.
+        #[weight = T::DbWeight::get().reads_writes(1,1) + 100_000]
+        pub fn set_enabled(origin,
+                           rid: T::ResourceId,
+                           enabled: bool,
+        ) -> DispatchResult {
+            Self::ensure_admin_or_root(origin)?;
+            Metadata::<T>::try_mutate(&rid, |metadata| -> DispatchResult {
+                let metadata = metadata.as_mut().ok_or(Error::<T>::NoMetadata)?;
+                metadata.enabled = enabled;
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::ResourceEnabled(rid, enabled));
             Ok(())
         }
     }
@@ -89,16 +244,27 @@ impl<T: Trait> Module<T> {
             .or_else(ensure_root)
     }
 
-    /// Add a new resource mapping in [Names]. Existing entries will be overwritten.
+    /// Add a new resource mapping in [Names]. The previous address of the resource is unmapped.
+    /// Fails if the address is mapped to another resource.
     pub fn set_resource(rid: T::ResourceId,
                         local_addr: T::Address,
-    ) {
+    ) -> DispatchResult {
+        if let Some(existing) = AddressToResource::<T>::get(&local_addr) {
+            ensure!(existing == rid, Error::<T>::AddressInUse);
+        }
+
+        // Remove the stale reverse mapping of the previous address
+        if let Some(previous) = ResourceToAddress::<T>::get(&rid) {
+            AddressToResource::<T>::remove(previous);
+        }
+
         // Add the mapping both ways
         ResourceToAddress::<T>::insert(rid.clone(), local_addr.clone());
         AddressToResource::<T>::insert(local_addr, rid);
+        Ok(())
     }
 
-    /// Remove a resource mapping in [Names].
+    /// Remove a resource mapping in [Names] and the metadata of the resource.
     pub fn remove_resource(rid: &T::ResourceId) {
         // If it doesn't exist for some unexpected reason, still allow removal by setting default
         let address = ResourceToAddress::<T>::get(rid).unwrap_or_default();
@@ -106,5 +272,54 @@ impl<T: Trait> Module<T> {
         // Remove the resource mapping both ways
         ResourceToAddress::<T>::remove(rid);
         AddressToResource::<T>::remove(address);
+        Metadata::<T>::remove(rid);
+    }
+
+    /// Add a new mapping of a resource to a local asset. The previous asset of the resource is
+    /// unmapped. Fails if the asset is mapped to another resource.
+    pub fn set_asset_resource(rid: T::ResourceId,
+                              asset_id: T::AssetId,
+    ) -> DispatchResult {
+        if let Some(existing) = AssetToResource::<T>::get(&asset_id) {
+            ensure!(existing == rid, Error::<T>::AssetInUse);
+        }
+        if let Some(previous) = ResourceToAsset::<T>::get(&rid) {
+            AssetToResource::<T>::remove(previous);
+        }
+
+        ResourceToAsset::<T>::insert(rid.clone(), asset_id.clone());
+        AssetToResource::<T>::insert(asset_id, rid);
+        Ok(())
+    }
+
+    /// Remove a mapping of a resource to a local asset.
+    pub fn remove_asset_resource(rid: &T::ResourceId) {
+        if let Some(asset_id) = ResourceToAsset::<T>::take(rid) {
+            AssetToResource::<T>::remove(asset_id);
+        }
+    }
+
+    /// Returns every resource mapped to an address
+    pub fn addresses() -> Vec<(T::ResourceId, T::Address)> {
+        ResourceToAddress::<T>::iter().collect()
+    }
+
+    /// Returns every resource mapped to a local asset
+    pub fn assets() -> Vec<(T::ResourceId, T::AssetId)> {
+        ResourceToAsset::<T>::iter().collect()
+    }
+
+    /// Returns the metadata of every resource that has metadata
+    pub fn metadata() -> Vec<(T::ResourceId, ResourceMetadata)> {
+        Metadata::<T>::iter().collect()
+    }
+
+    /// Returns true if the resource is enabled and may be transferred to the chain. Resources
+    /// without metadata are enabled for any chain.
+    pub fn is_transferable(rid: &T::ResourceId, chain_id: u8) -> bool {
+        Self::metadata_of(rid).map_or(true, |metadata| {
+            metadata.enabled
+                && (metadata.allowed_chains.is_empty() || metadata.allowed_chains.contains(&chain_id))
+        })
     }
 }
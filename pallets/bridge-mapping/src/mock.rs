@@ -51,8 +51,10 @@ impl system::Trait for Test {
 }
 
 impl Trait for Test {
+    type Event = ();
     type ResourceId = u32;
     type Address = u32;
+    type AssetId = u32;
     type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
 }
 
@@ -1,4 +1,4 @@
-use crate::{mock::*};
+use crate::{mock::*, Error, ResourceKind, ResourceMetadata};
 use frame_support::{assert_ok, assert_err};
 
 #[test]
@@ -25,6 +25,53 @@ fn set_resource_updates_existing_storage() {
         // Check that resource mapping was added to storage
         assert_eq!(SUT::addr_of(1), Some(3));
         assert_eq!(SUT::name_of(3), Some(1));
+        // The previous address is no longer mapped
+        assert_eq!(SUT::name_of(2), None);
+    });
+}
+
+#[test]
+fn set_resource_rejects_address_of_other_resource() {
+    new_test_ext().execute_with(|| {
+        let admin       = Origin::root();
+        assert_ok!( SUT::set(admin.clone(), 1, 2) );
+        assert_err!( SUT::set(admin, 4, 2), Error::<Test>::AddressInUse );
+
+        assert_eq!(SUT::addr_of(4), None);
+        assert_eq!(SUT::name_of(2), Some(1));
+    });
+}
+
+#[test]
+fn metadata_controls_transfers() {
+    new_test_ext().execute_with(|| {
+        let admin       = Origin::root();
+        let resource_id = 1;
+
+        // Resources without metadata can be transferred anywhere
+        assert!(SUT::is_transferable(&resource_id, 5));
+        assert_err!( SUT::set_enabled(admin.clone(), resource_id, false), Error::<Test>::NoMetadata );
+
+        let metadata = ResourceMetadata {
+            kind: ResourceKind::Nft,
+            decimals: 0,
+            allowed_chains: vec![1, 2],
+            enabled: true,
+        };
+        assert_err!( SUT::set_metadata(Origin::signed(0), resource_id, metadata.clone()),
+                     sp_runtime::traits::BadOrigin );
+        assert_ok!( SUT::set_metadata(admin.clone(), resource_id, metadata.clone()) );
+        assert_eq!(SUT::metadata_of(resource_id), Some(metadata));
+        assert!(SUT::is_transferable(&resource_id, 1));
+        assert!(!SUT::is_transferable(&resource_id, 5));
+
+        assert_ok!( SUT::set_enabled(admin.clone(), resource_id, false) );
+        assert!(!SUT::is_transferable(&resource_id, 1));
+
+        // Removing the resource removes its metadata
+        assert_ok!( SUT::set(admin.clone(), resource_id, 2) );
+        assert_ok!( SUT::remove(admin, resource_id) );
+        assert_eq!(SUT::metadata_of(resource_id), None);
     });
 }
 
@@ -66,3 +113,34 @@ fn non_admin_cannot_remove() {
                     sp_runtime::traits::BadOrigin);
     });
 }
+
+#[test]
+fn set_and_remove_asset() {
+    new_test_ext().execute_with(|| {
+        let admin = Origin::root();
+        assert_err!(SUT::set_asset(Origin::signed(0), 1, 7),
+                    sp_runtime::traits::BadOrigin);
+
+        assert_ok!( SUT::set_asset(admin.clone(), 1, 7) );
+        assert_eq!(SUT::asset_of(1), Some(7));
+        assert_eq!(SUT::resource_of_asset(7), Some(1));
+
+        assert_ok!( SUT::remove_asset(admin, 1) );
+        assert_eq!(SUT::asset_of(1), None);
+        assert_eq!(SUT::resource_of_asset(7), None);
+    });
+}
+
+#[test]
+fn list_mappings() {
+    new_test_ext().execute_with(|| {
+        let admin = Origin::root();
+        assert_ok!( SUT::set(admin.clone(), 1, 2) );
+        assert_ok!( SUT::set_asset(admin.clone(), 3, 7) );
+        assert_ok!( SUT::set_metadata(admin, 3, ResourceMetadata::default()) );
+
+        assert_eq!(SUT::addresses(), vec![(1, 2)]);
+        assert_eq!(SUT::assets(), vec![(3, 7)]);
+        assert_eq!(SUT::metadata(), vec![(3, ResourceMetadata::default())]);
+    });
+}
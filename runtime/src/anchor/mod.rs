@@ -4,7 +4,7 @@
 //! For a more formally detailed explanation refer section 3.4 of
 //! [Centrifuge Protocol Paper](https://staticw.centrifuge.io/assets/centrifuge_os_protocol_paper.pdf)
 
-use crate::{common, fees};
+use crate::{common, fees::{self, FeeKey}};
 use codec::{Decode, Encode};
 use frame_support::{
     decl_module, decl_storage,
@@ -155,7 +155,7 @@ decl_module! {
         /// using smaller `stored_until_date`s. Computation cost involves timestamp calculations
         /// and state rent calculations, which we take here to be equivalent to a transfer transaction.
         /// # </weight>
-        #[weight = 190_000_000]
+        #[weight = <fees::Module<T>>::charge_weight().saturating_add(190_000_000)]
         pub fn commit(origin, anchor_id_preimage: T::Hash, doc_root: T::Hash, proof: T::Hash, stored_until_date: T::Moment) -> DispatchResult {
             let who = ensure_signed(origin)?;
             ensure!(<pallet_timestamp::Module<T>>::get() + T::Moment::from(common::MS_PER_DAY.try_into().unwrap()) < stored_until_date,
@@ -178,17 +178,10 @@ decl_module! {
             }
 
              // pay the state rent
-            let today_in_days_from_epoch = TryInto::<u64>::try_into(<pallet_timestamp::Module<T>>::get())
-                .map(common::get_days_since_epoch)
-                .map_err(|_e| "Can not convert timestamp to u64")
-                .unwrap();
-
-            // we use the fee config setup on genesis for anchoring to calculate the state rent
-            let fee = <fees::Module<T>>::price_of(Self::fee_key()).unwrap() *
-                <T as pallet_balances::Trait>::Balance::from(stored_until_date_from_epoch - today_in_days_from_epoch);
+            let fee = Self::rent_until(stored_until_date).ok_or("Anchor rent fee not set")?;
 
-            // pay state rent to block author
-            <fees::Module<T>>::pay_fee_to_author(who, fee)?;
+            // pay state rent to the configured destinations
+            <fees::Module<T>>::charge(&who, FeeKey::AnchorRentPerDay, fee)?;
 
             let block_num = <frame_system::Module<T>>::block_number();
             let anchor_data = AnchorData {
@@ -273,6 +266,21 @@ decl_module! {
 }
 
 impl<T: Trait> Module<T> {
+    /// Returns the state rent for storing an anchor from today until the given date, based on
+    /// the anchoring rent fee per day.
+    pub fn rent_until(stored_until_date: T::Moment) -> Option<<T as pallet_balances::Trait>::Balance> {
+        let today_in_days_from_epoch = TryInto::<u64>::try_into(<pallet_timestamp::Module<T>>::get())
+            .map(common::get_days_since_epoch)
+            .ok()?;
+        let stored_until_date_from_epoch = TryInto::<u64>::try_into(stored_until_date)
+            .map(common::get_days_since_epoch)
+            .ok()?;
+        let rent_per_day = <fees::Module<T>>::price_of(FeeKey::AnchorRentPerDay)?;
+
+        Some(rent_per_day *
+            <T as pallet_balances::Trait>::Balance::from(stored_until_date_from_epoch.saturating_sub(today_in_days_from_epoch)))
+    }
+
     /// Checks if the given `anchor_id` has a valid pre-commit, i.e it has a pre-commit with
     /// `expiration_block` < `current_block_number`.
     fn has_valid_pre_commit(anchor_id: T::Hash) -> bool {
@@ -420,10 +428,6 @@ impl<T: Trait> Module<T> {
         <AnchorIndexes<T>>::insert(idx, &anchor_id);
         LatestAnchorIndex::put(idx);
     }
-
-    fn fee_key() -> <T as frame_system::Trait>::Hash {
-        <T as frame_system::Trait>::Hashing::hash_of(&0)
-    }
 }
 
 /// tests for anchor module
@@ -64,6 +64,13 @@ impl pallet_timestamp::Trait for Test {
 impl fees::Trait for Test {
     type Event = ();
     type FeeChangeOrigin = frame_system::EnsureRoot<u64>;
+    type Author = ();
+    type TreasuryAccount = ();
+    type StakingPotAccount = ();
+    type CreditAccount = ();
+    type SpendOrigin = frame_system::EnsureRoot<u64>;
+    type MaxFeeHistory = ();
+    type CfgPrice = ();
 }
 
 parameter_types! {
@@ -127,12 +134,10 @@ fn new_test_ext() -> sp_io::TestExternalities {
         .build_storage::<Test>()
         .unwrap();
     fees::GenesisConfig::<Test> {
+        destinations: vec![],
         initial_fees: vec![(
             // anchoring state rent fee per day
-            H256::from(&[
-                17, 218, 109, 31, 118, 29, 223, 155, 219, 76, 157, 110, 83, 3, 235, 212, 31, 97,
-                133, 141, 10, 86, 71, 161, 167, 191, 224, 137, 191, 146, 27, 233,
-            ]),
+            FeeKey::AnchorRentPerDay,
             // state rent 0 for tests
             0,
         )],
@@ -0,0 +1,260 @@
+//! # Multi Assets
+//!
+//! Balances of fungible assets other than the native token, e.g. ERC-20 tokens bridged from
+//! Ethereum such as DAI. Assets are created by an admin and their supply is only changed by
+//! other modules through [Module::mint] and [Module::burn], e.g. when tokens are received from or
+//! sent to another chain. Holders can transfer their balances freely.
+
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::EnsureOrigin,
+    Parameter,
+};
+use frame_system::{ensure_root, ensure_signed};
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Member},
+    RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+/// Identifier of a fungible asset
+pub type AssetId = u32;
+
+/// Metadata of a fungible asset
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct AssetMetadata {
+    /// Ticker symbol of the asset, e.g. DAI
+    pub symbol: Vec<u8>,
+    /// Number of decimals of the asset's smallest unit
+    pub decimals: u8,
+}
+
+pub trait Trait: frame_system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Type of asset balances
+    type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy;
+    /// Origin that can create assets
+    type AdminOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Assets {
+        /// Metadata of every existing asset
+        Metadata get(fn metadata_of): map hasher(twox_64_concat) AssetId => Option<AssetMetadata>;
+
+        /// Total amount of an asset held by all accounts
+        TotalSupply get(fn total_supply): map hasher(twox_64_concat) AssetId => T::Balance;
+
+        /// Amount of an asset held by an account
+        Balances get(fn balance_of): double_map hasher(twox_64_concat) AssetId, hasher(blake2_128_concat) T::AccountId => T::Balance;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+        Balance = <T as Trait>::Balance,
+    {
+        /// The asset was created
+        Created(AssetId, AssetMetadata),
+        /// The amount of the asset was issued to the account
+        Minted(AssetId, AccountId, Balance),
+        /// The amount of the asset was destroyed from the account
+        Burned(AssetId, AccountId, Balance),
+        /// The amount of the asset was transferred from the first account to the second
+        Transferred(AssetId, AccountId, AccountId, Balance),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// An asset with the id already exists
+        AssetExists,
+        /// No asset with the id exists
+        UnknownAsset,
+        /// The account does not hold enough of the asset
+        InsufficientBalance,
+        /// The total supply of the asset would overflow
+        Overflow,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+        fn deposit_event() = default;
+
+        /// Create a new asset without any supply
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(1, 1) + 195_000_000]
+        pub fn create(origin, asset_id: AssetId, metadata: AssetMetadata) -> DispatchResult {
+            T::AdminOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            ensure!(!Metadata::contains_key(asset_id), Error::<T>::AssetExists);
+            Metadata::insert(asset_id, &metadata);
+
+            Self::deposit_event(RawEvent::Created(asset_id, metadata));
+            Ok(())
+        }
+
+        /// Transfer `amount` of the asset from the caller to `dest`
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2, 2) + 195_000_000]
+        pub fn transfer(origin, asset_id: AssetId, dest: T::AccountId, amount: T::Balance) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Metadata::contains_key(asset_id), Error::<T>::UnknownAsset);
+
+            let from_balance = Self::balance_of(asset_id, &who)
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::InsufficientBalance)?;
+            <Balances<T>>::insert(asset_id, &who, from_balance);
+            // Balances are bounded by the total supply, so this can not overflow
+            <Balances<T>>::mutate(asset_id, &dest, |balance| *balance += amount);
+
+            Self::deposit_event(RawEvent::Transferred(asset_id, who, dest, amount));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Issues the amount of the asset to the account
+    pub fn mint(asset_id: AssetId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        ensure!(Metadata::contains_key(asset_id), Error::<T>::UnknownAsset);
+        let supply = Self::total_supply(asset_id)
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+
+        <TotalSupply<T>>::insert(asset_id, supply);
+        <Balances<T>>::mutate(asset_id, who, |balance| *balance += amount);
+
+        Self::deposit_event(RawEvent::Minted(asset_id, who.clone(), amount));
+        Ok(())
+    }
+
+    /// Destroys the amount of the asset held by the account
+    pub fn burn(asset_id: AssetId, who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+        ensure!(Metadata::contains_key(asset_id), Error::<T>::UnknownAsset);
+        let balance = Self::balance_of(asset_id, who)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::InsufficientBalance)?;
+
+        <Balances<T>>::insert(asset_id, who, balance);
+        <TotalSupply<T>>::mutate(asset_id, |supply| *supply -= amount);
+
+        Self::deposit_event(RawEvent::Burned(asset_id, who.clone(), amount));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight};
+    use sp_core::H256;
+    use sp_runtime::{
+        testing::Header,
+        traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+        Perbill,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test where system = frame_system {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl frame_system::Trait for Test {
+        type BaseCallFilter = ();
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type DbWeight = ();
+        type BlockExecutionWeight = ();
+        type ExtrinsicBaseWeight = ();
+        type MaximumExtrinsicWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type ModuleToIndex = ();
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+    }
+
+    impl Trait for Test {
+        type Event = ();
+        type Balance = u128;
+        type AdminOrigin = frame_system::EnsureRoot<u64>;
+    }
+
+    type Assets = Module<Test>;
+
+    const DAI: AssetId = 1;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap()
+            .into()
+    }
+
+    fn dai() -> AssetMetadata {
+        AssetMetadata { symbol: b"DAI".to_vec(), decimals: 18 }
+    }
+
+    #[test]
+    fn create_asset() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(Assets::create(Origin::signed(1), DAI, dai()), BadOrigin);
+            assert_noop!(Assets::mint(DAI, &1, 10), Error::<Test>::UnknownAsset);
+
+            assert_ok!(Assets::create(Origin::root(), DAI, dai()));
+            assert_eq!(Assets::metadata_of(DAI), Some(dai()));
+            assert_noop!(Assets::create(Origin::root(), DAI, dai()), Error::<Test>::AssetExists);
+        });
+    }
+
+    #[test]
+    fn mint_transfer_and_burn() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Assets::create(Origin::root(), DAI, dai()));
+            assert_ok!(Assets::mint(DAI, &1, 100));
+            assert_eq!(Assets::total_supply(DAI), 100);
+
+            assert_ok!(Assets::transfer(Origin::signed(1), DAI, 2, 40));
+            assert_noop!(Assets::transfer(Origin::signed(2), DAI, 1, 41), Error::<Test>::InsufficientBalance);
+            assert_eq!(Assets::balance_of(DAI, 1), 60);
+            assert_eq!(Assets::balance_of(DAI, 2), 40);
+
+            assert_noop!(Assets::burn(DAI, &2, 41), Error::<Test>::InsufficientBalance);
+            assert_ok!(Assets::burn(DAI, &2, 40));
+            assert_eq!(Assets::balance_of(DAI, 2), 0);
+            assert_eq!(Assets::total_supply(DAI), 60);
+        });
+    }
+}
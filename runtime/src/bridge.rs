@@ -1,19 +1,27 @@
-use crate::nft;
+use crate::{assets, nft};
 use bridge_mapping;
 use core::convert::TryInto;
 use codec::{Decode, Encode};
-use unique_assets::traits::Unique;
-use crate::va_registry::types::{RegistryId, AssetId, TokenId};
-use crate::{fees, constants::currency};
-use frame_support::traits::{Currency, ExistenceRequirement::AllowDeath, Get};
+use crate::va_registry::types::{RegistryId, AssetId, AssetInfo, TokenId};
+use crate::fees::{self, FeeKey};
+use frame_support::traits::{
+    Currency, ExistenceRequirement::{AllowDeath, KeepAlive}, Get, ReservableCurrency,
+    WithdrawReason,
+};
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
-    traits::EnsureOrigin,
+    storage::migration, traits::EnsureOrigin, weights::Weight,
 };
-use frame_system::{self as system, ensure_signed, ensure_root};
+use frame_system::{self as system, ensure_root, ensure_signed};
+use unique_assets::traits::{Mintable, Unique};
 use sp_core::U256;
-use sp_runtime::traits::SaturatedConversion;
-use sp_std::prelude::*;
+use sp_runtime::{
+    traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
+    ModuleId, Perbill, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+#[cfg(feature = "std")]
+use serde::Serialize;
 
 /// Abstract identifer of an asset, for a common vocabulary across chains.
 pub type ResourceId = chainbridge::ResourceId;
@@ -22,14 +30,12 @@ pub type ResourceId = chainbridge::ResourceId;
 /// registry id (20 bytes) or a fungible asset type (in the future). Constrained to 32 bytes just
 /// as an upper bound to store efficiently.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
 pub struct Address(pub Bytes32);
 
 /// Length of an [Address] type
 const ADDR_LEN: usize = 32;
 
-/// Additional Fee charged when moving NFTs to target chains (RAD)
-const NFT_TOKEN_FEE: u128 = 20 * currency::CFG;
-
 type Bytes32 = [u8; ADDR_LEN];
 
 type BalanceOf<T> =
@@ -58,26 +64,196 @@ impl From<Address> for Bytes32 {
     }
 }
 
+/// Direction of bridge transfers, as seen from this chain
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// Transfers from this chain to another chain
+    Outgoing,
+    /// Transfers from another chain to this chain
+    Incoming,
+}
+
+/// Number of buckets the outflow of a period is tracked in, see [TransferLimits]
+pub const OUTFLOW_BUCKETS: u32 = 24;
+
+/// Limits of native token transfers to a destination chain. Fungible assets are not limited, as
+/// only wrapped assets minted by incoming transfers can be burned by outgoing transfers, which
+/// cannot drain the native tokens locked in the bridge.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+pub struct TransferLimits<Balance, BlockNumber> {
+    /// Maximum amount of a single transfer
+    pub max_per_transfer: Option<Balance>,
+    /// Maximum amount transferred within any period of consecutive blocks
+    pub max_per_period: Option<Balance>,
+    /// Number of blocks of a period
+    pub period: BlockNumber,
+}
+
+/// Fee of a bridge transfer, as a flat part plus basis points of the transferred amount
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+pub struct BridgeFee<Balance> {
+    #[cfg_attr(feature = "std", serde(serialize_with = "fees::serialize_as_string"))]
+    pub flat: Balance,
+    /// Basis points of the amount, i.e. 1/10_000
+    pub bps: u32,
+}
+
+/// Basis points of a whole
+const MAX_BPS: u32 = 10_000;
+
+impl<Balance: sp_runtime::traits::AtLeast32BitUnsigned + Copy> BridgeFee<Balance> {
+    /// Returns the fee of a transfer of the amount
+    pub fn of(&self, amount: Balance) -> Balance {
+        // 1 basis point is 100 parts per million
+        self.flat.saturating_add(Permill::from_parts(self.bps.saturating_mul(100)) * amount)
+    }
+}
+
+/// Account that pays the rewards of relayers out of its share of the bridge fees
+const RELAYER_POT_ID: ModuleId = ModuleId(*b"cb/rlyrs");
+
+/// Account holding the bonds of relayers, apart from any reserve of the relayers
+const RELAYER_BONDS_ID: ModuleId = ModuleId(*b"cb/bonds");
+
+/// Incentives of relayers for voting on proposals that were executed
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+pub struct RelayerIncentives<Balance> {
+    /// Share of every bridge fee that is paid into the relayer reward pot
+    pub fee_share: Perbill,
+    /// Reward of an executed proposal, split between the bonded relayers that voted for it
+    pub reward_per_proposal: Balance,
+    /// Bond a relayer must hold to be rewarded
+    pub min_bond: Balance,
+}
+
+/// Participation of a relayer in executed proposals
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+pub struct RelayerStats<Balance> {
+    /// Number of executed proposals the relayer voted for
+    pub votes_for: u32,
+    /// Number of executed proposals the relayer voted against
+    pub votes_against: u32,
+    /// Total rewards paid to the relayer
+    pub rewarded: Balance,
+    /// Total amount slashed from the relayer's bond
+    pub slashed: Balance,
+}
+
+/// Fees of bridge transfers
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+pub struct FeeSchedule<Balance> {
+    /// Fee of native token transfers without a bridge fee, formerly the token transfer fee
+    #[cfg_attr(feature = "std", serde(serialize_with = "fees::serialize_option_as_string"))]
+    pub native: Option<Balance>,
+    /// Fee of nft transfers without a bridge fee
+    #[cfg_attr(feature = "std", serde(serialize_with = "fees::serialize_option_as_string"))]
+    pub nft: Option<Balance>,
+    /// Fee of fungible asset transfers without a bridge fee
+    #[cfg_attr(feature = "std", serde(serialize_with = "fees::serialize_option_as_string"))]
+    pub fungible: Option<Balance>,
+    /// Fees set per destination chain and resource
+    pub bridge_fees: Vec<(chainbridge::ChainId, ResourceId, BridgeFee<Balance>)>,
+}
+
+/// Relayers of the bridge and the number of votes required to execute a proposal
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RelayerSet<AccountId> {
+    /// Relayers added through this module
+    pub relayers: Vec<AccountId>,
+    /// Number of all relayers, including those missing from `relayers`
+    pub count: u32,
+    pub threshold: u32,
+}
+
+/// Everything known on chain about a resource
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ResourceInfo {
+    pub resource_id: ResourceId,
+    /// Method executing proposals of the resource, as registered with the chainbridge
+    pub method: Option<Vec<u8>>,
+    /// Local address, e.g. a registry, the resource is mapped to
+    pub address: Option<Address>,
+    /// Local fungible asset the resource is mapped to
+    pub asset_id: Option<assets::AssetId>,
+    pub metadata: Option<bridge_mapping::ResourceMetadata>,
+}
+
 pub trait Trait: system::Trait
                + fees::Trait
                + pallet_balances::Trait
                + chainbridge::Trait
-               + nft::Trait
-               + bridge_mapping::Trait {
+               + nft::Trait<AssetInfo = AssetInfo>
+               + assets::Trait
+               + bridge_mapping::Trait<AssetId = assets::AssetId> {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     /// Specifies the origin check provided by the chainbridge for calls that can only be called by the chainbridge pallet
     type BridgeOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
-    type Currency: Currency<Self::AccountId>;
+    type Currency: ReservableCurrency<Self::AccountId>;
     /// Ids can be defined by the runtime and passed in, perhaps from blake2b_128 hashes.
     type HashId: Get<ResourceId>;
     type NativeTokenId: Get<ResourceId>;
 	type AdminOrigin: EnsureOrigin<Self::Origin>;
+    /// Number of blocks an unbonded amount stays bonded, so that former relayers can still be
+    /// slashed for their votes
+    type BondingDuration: Get<Self::BlockNumber>;
 }
 
 decl_storage! {
     trait Store for Module<T: Trait> as PalletBridge {
-    	/// Additional Fee charged when moving native tokens to target chains (CFG)
-    	TokenTransferFee get(fn token_transfer_fee) :T::Balance = (2000 * currency::CFG).saturated_into();
+        /// Limits of native token transfers per destination chain
+        Limits get(fn limits_of): map hasher(blake2_128_concat) chainbridge::ChainId => Option<TransferLimits<BalanceOf<T>, T::BlockNumber>>;
+
+        /// Outflow to the destination chain within the last period, by the first block of each
+        /// bucket of blocks it was transferred in, oldest first
+        Outflows get(fn outflow_of): map hasher(blake2_128_concat) chainbridge::ChainId => Vec<(T::BlockNumber, BalanceOf<T>)>;
+
+        /// Transfers in a paused direction are rejected
+        Paused get(fn paused): map hasher(blake2_128_concat) Direction => bool;
+
+        /// Fee of transfers of a resource to a destination chain. Transfers without a fee set here
+        /// pay the fee of the fees module.
+        BridgeFees get(fn bridge_fee_of): double_map hasher(blake2_128_concat) chainbridge::ChainId, hasher(blake2_128_concat) ResourceId => Option<BridgeFee<BalanceOf<T>>>;
+
+        /// Account receiving the bridge fees. Fees are charged by the fees module if not set.
+        FeeAccount get(fn fee_account): Option<T::AccountId>;
+
+        /// Incentives of relayers, paid out of the bridge fees
+        Incentives get(fn relayer_incentives): RelayerIncentives<BalanceOf<T>>;
+
+        /// Amount a relayer has bonded, held by the bond account
+        Bonds get(fn bond_of): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+        /// Amount a former relayer has unbonded, still held by the bond account until the block it
+        /// can be withdrawn at
+        Unbonding get(fn unbonding_of): map hasher(blake2_128_concat) T::AccountId => Option<(BalanceOf<T>, T::BlockNumber)>;
+
+        /// Participation of a relayer in executed proposals
+        Stats get(fn relayer_stats): map hasher(blake2_128_concat) T::AccountId => RelayerStats<BalanceOf<T>>;
+
+        /// Executed proposals whose votes have been added to the participation of their relayers
+        Recorded get(fn recorded): double_map hasher(blake2_128_concat) chainbridge::ChainId, hasher(blake2_128_concat) chainbridge::DepositNonce => bool;
+
+        /// Executed proposals whose relayers have been rewarded
+        Rewarded get(fn rewarded): double_map hasher(blake2_128_concat) chainbridge::ChainId, hasher(blake2_128_concat) chainbridge::DepositNonce => bool;
+
+        /// Accounts known to have been relayers, as the relayer set of the chainbridge can not be
+        /// iterated through its interface. Relayers are added and removed through
+        /// [Module::add_relayer] and [Module::remove_relayer] to be known, relayers registered
+        /// before are indexed on the runtime upgrade.
+        KnownRelayers: map hasher(blake2_128_concat) T::AccountId => ();
+
+        /// Ids of the whitelisted chains, as the whitelist of the chainbridge can not be iterated.
+        /// Chains are whitelisted through [Module::whitelist_chain] to be known.
+        WhitelistedChains: Vec<chainbridge::ChainId>;
     }
 
     add_extra_genesis {
@@ -92,9 +268,55 @@ decl_storage! {
 
 decl_event! {
     pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
         <T as frame_system::Trait>::Hash,
+        <T as frame_system::Trait>::BlockNumber,
+        Balance = BalanceOf<T>,
+        AssetBalance = <T as assets::Trait>::Balance,
     {
         Remark(Hash, ResourceId),
+        /// Native tokens were sent to another chain with the sender, recipient, amount, fee,
+        /// destination chain, resource id and deposit nonce of the relayed transfer
+        NativeTransferSent(AccountId, Vec<u8>, Balance, Balance, chainbridge::ChainId, ResourceId, chainbridge::DepositNonce),
+        /// The asset was locked and sent to another chain with the sender, recipient, asset, fee,
+        /// destination chain, resource id and deposit nonce of the relayed transfer
+        AssetTransferSent(AccountId, Vec<u8>, AssetId, Balance, chainbridge::ChainId, ResourceId, chainbridge::DepositNonce),
+        /// The amount of a fungible asset was burned and sent to another chain with the sender,
+        /// recipient, asset, amount, fee, destination chain, resource id and deposit nonce of the
+        /// relayed transfer
+        FungibleAssetTransferSent(AccountId, Vec<u8>, assets::AssetId, AssetBalance, Balance, chainbridge::ChainId, ResourceId, chainbridge::DepositNonce),
+        /// The amount of a fungible asset of the resource was received from another chain and minted
+        FungibleAssetTransferReceived(AccountId, assets::AssetId, AssetBalance, ResourceId),
+        /// Native tokens of the resource were received from another chain
+        NativeTransferReceived(AccountId, Balance, ResourceId),
+        /// The asset of the resource was received from another chain
+        AssetTransferReceived(AccountId, AssetId, ResourceId),
+        /// Limits of native token transfers to the chain were set or removed
+        TransferLimitsSet(chainbridge::ChainId, Option<TransferLimits<Balance, BlockNumber>>),
+        /// Transfers in the direction are rejected until unpaused
+        Paused(Direction),
+        /// Transfers in the direction are accepted again
+        Unpaused(Direction),
+        /// The fee of transfers of the resource to the chain was set or removed
+        BridgeFeeSet(chainbridge::ChainId, ResourceId, Option<BridgeFee<Balance>>),
+        /// The account receiving bridge fees was set or removed
+        FeeAccountSet(Option<AccountId>),
+        /// The incentives of relayers were set
+        RelayerIncentivesSet(RelayerIncentives<Balance>),
+        /// The relayer bonded the amount
+        RelayerBonded(AccountId, Balance),
+        /// The relayer unbonded the amount of its bond, which can be withdrawn from the block
+        RelayerUnbonded(AccountId, Balance, BlockNumber),
+        /// The former relayer withdrew the unbonded amount
+        UnbondedWithdrawn(AccountId, Balance),
+        /// The amount was slashed from the bond of the relayer
+        RelayerSlashed(AccountId, Balance),
+        /// Relayers that voted for the executed proposal of the chain and nonce were paid the
+        /// reward each
+        RelayersRewarded(chainbridge::ChainId, chainbridge::DepositNonce, Balance),
+        /// The votes on the executed proposal of the chain and nonce were recorded, but the
+        /// reward pot can not pay the rewards yet
+        RelayerVotesRecorded(chainbridge::ChainId, chainbridge::DepositNonce),
     }
 }
 
@@ -105,6 +327,38 @@ decl_error! {
         /// Registry id provided on recieving a transfer is not a key in bridges-names mapping.
         RegistryIdDoesNotExist,
         InvalidTransfer,
+        /// Transfers in this direction are paused
+        TransfersPaused,
+        /// Amount exceeds the maximum of a single transfer to the destination chain
+        TransferTooLarge,
+        /// Amount exceeds what can still be transferred to the destination chain in this period
+        OutflowCapExceeded,
+        /// A period of zero blocks can not cap the outflow
+        InvalidLimits,
+        /// Basis points exceed the whole amount
+        InvalidFee,
+        /// No fee is set for transfers of the resource to the destination chain
+        FeeNotSet,
+        /// The resource is disabled or may not be transferred to the destination chain
+        ResourceNotTransferable,
+        /// Only nfts of resources marked as foreign are minted on receipt
+        NotForeignResource,
+        /// Only relayers can bond
+        NotRelayer,
+        /// A relayer can only unbond once removed from the relayer set
+        StillRelayer,
+        /// The bond of the relayer is less than the amount
+        InsufficientBond,
+        /// Nothing has been unbonded
+        NotUnbonding,
+        /// The unbonded amount can not be withdrawn yet
+        StillUnbonding,
+        /// No votes exist for the proposal
+        ProposalNotFound,
+        /// The proposal has not been executed
+        ProposalNotExecuted,
+        /// The relayers of the proposal have already been rewarded
+        AlreadyRewarded,
     }
 }
 
@@ -113,14 +367,46 @@ decl_module! {
         const HashId: ResourceId = T::HashId::get();
         const NativeTokenId: ResourceId = T::NativeTokenId::get();
 
+        type Error = Error<T>;
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            if WhitelistedChains::exists() {
+                return 0;
+            }
+            // Index the chains whitelisted before the index was kept
+            let chains = (0..=chainbridge::ChainId::max_value())
+                .filter(|chain_id| <chainbridge::Module<T>>::chain_whitelisted(*chain_id))
+                .collect::<Vec<_>>();
+            WhitelistedChains::put(chains);
+
+            // Index the relayers registered before the index was kept. Keys of the relayers of the
+            // chainbridge are blake2_128_concat hashed, i.e. the account follows 16 bytes
+            let relayers: Vec<_> = migration::StorageIterator::<bool>::new(b"ChainBridge", b"Relayers")
+                .collect();
+            let read = relayers.len() as Weight;
+            let mut written: Weight = 0;
+            for (key, registered) in relayers {
+                if let Some(relayer) = key.get(16..).and_then(|mut key| T::AccountId::decode(&mut key).ok()) {
+                    if registered {
+                        <KnownRelayers<T>>::insert(&relayer, ());
+                        written += 1;
+                    }
+                }
+            }
+
+            // The index, all 256 chain ids and the relayers are read
+            T::DbWeight::get().reads_writes(257 + read, 1 + written)
+        }
+
         /// Transfers some amount of the native token to some recipient on a (whitelisted) destination chain.
-        #[weight = 195_000_000]
+        #[weight = <fees::Module<T>>::charge_weight().saturating_add(195_000_000)]
         pub fn transfer_native(origin, amount: BalanceOf<T>, recipient: Vec<u8>, dest_id: chainbridge::ChainId) -> DispatchResult {
             let source = ensure_signed(origin)?;
 
-            let token_fee: T::Balance = TokenTransferFee::<T>::get();
+            let resource_id = T::NativeTokenId::get();
+            let token_fee = Self::quote(FeeKey::NativeBridge, dest_id, resource_id, amount)
+                .ok_or(Error::<T>::FeeNotSet)?;
 			let total_amount = U256::from(amount.saturated_into()).saturating_add(U256::from(token_fee.saturated_into()));
 
             // Ensure account has enough balance for both fee and transfer
@@ -129,21 +415,29 @@ decl_module! {
             ensure!(U256::from(current_balance.saturated_into()) >= total_amount, "Insufficient Balance");
 
             ensure!(<chainbridge::Module<T>>::chain_whitelisted(dest_id), Error::<T>::InvalidTransfer);
+            Self::ensure_not_paused(Direction::Outgoing)?;
+            let outflow = Self::outflow_after(dest_id, amount)?;
 
-            // Burn additional fees
-            <fees::Module<T>>::burn_fee(&source, token_fee)?;
+            // Pay additional fees
+            Self::pay_bridge_fee(&source, FeeKey::NativeBridge, token_fee)?;
 
             let bridge_id = <chainbridge::Module<T>>::account_id();
             T::Currency::transfer(&source, &bridge_id, amount.into(), AllowDeath)?;
 
-            let resource_id = T::NativeTokenId::get();
-            <chainbridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient, U256::from(amount.saturated_into()))?;
+            <chainbridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient.clone(), U256::from(amount.saturated_into()))?;
+
+            if let Some(outflow) = outflow {
+                <Outflows<T>>::insert(dest_id, outflow);
+            }
+
+            let nonce = <chainbridge::Module<T>>::deposit_counts(dest_id);
+            Self::deposit_event(RawEvent::NativeTransferSent(source, recipient, amount, token_fee, dest_id, resource_id, nonce));
             Ok(())
         }
 
         /// Transfer an nft to a whitelisted destination chain. Source nft is locked in bridge account
         /// rather than being burned.
-        #[weight = 195_000_000]
+        #[weight = <fees::Module<T>>::charge_weight().saturating_add(195_000_000)]
         pub fn transfer_asset(origin,
                               recipient: Vec<u8>,
                               from_registry: RegistryId,
@@ -151,22 +445,25 @@ decl_module! {
                               dest_id: chainbridge::ChainId,
         ) -> DispatchResult {
             let source = ensure_signed(origin)?;
+            Self::ensure_not_paused(Direction::Outgoing)?;
 
             // Get resource id from registry
-            let reg: Address = from_registry.into();
-            let reg: Bytes32 = reg.into();
-            let reg: <T as bridge_mapping::Trait>::Address = reg.into();
-            let resource_id = <bridge_mapping::Module<T>>::name_of(reg)
+            let resource_id = Self::resource_id_of(from_registry)
                 .ok_or(Error::<T>::ResourceIdDoesNotExist)?;
+            Self::ensure_transferable(resource_id, dest_id)?;
 
-            // Burn additional fees
-            let nft_fee: T::Balance = NFT_TOKEN_FEE.saturated_into();
-            <fees::Module<T>>::burn_fee(&source, nft_fee)?;
+            // Pay additional fees
+            let token_fee = Self::quote(FeeKey::NftBridge, dest_id, resource_id, Zero::zero())
+                .ok_or(Error::<T>::FeeNotSet)?;
+            Self::pay_bridge_fee(&source, FeeKey::NftBridge, token_fee)?;
 
             // Lock asset by transferring to bridge account
             let bridge_id = <chainbridge::Module<T>>::account_id();
             let asset_id = AssetId(from_registry, token_id);
-            <nft::Module<T> as Unique>::transfer(&source, &bridge_id, &asset_id)?;
+            <nft::Module<T>>::lock(&source, &bridge_id, &asset_id)?;
+            let metadata = <nft::Module<T>>::asset(from_registry, token_id)
+                .map(|info| info.metadata)
+                .unwrap_or_default();
 
             // Transfer instructions for relayer
             let tid: &mut [u8] = &mut[0; 32];
@@ -175,40 +472,207 @@ decl_module! {
             <chainbridge::Module<T>>::transfer_nonfungible(dest_id,
                                                            resource_id.into(),
                                                            tid.to_vec(),
-                                                           recipient,
-                                                           vec![]/*assetinfo.metadata*/)
+                                                           recipient.clone(),
+                                                           metadata)?;
+
+            let nonce = <chainbridge::Module<T>>::deposit_counts(dest_id);
+            Self::deposit_event(RawEvent::AssetTransferSent(source, recipient, asset_id, token_fee, dest_id, resource_id, nonce));
+            Ok(())
+        }
+
+        /// Moves the amount from a relayer to the bond account as its bond. Only bonded relayers
+        /// are rewarded.
+        #[weight = 195_000_000]
+        pub fn bond(origin, amount: BalanceOf<T>) -> DispatchResult {
+            let relayer = ensure_signed(origin)?;
+            ensure!(<chainbridge::Module<T>>::is_relayer(&relayer), Error::<T>::NotRelayer);
+
+            T::Currency::transfer(&relayer, &Self::bond_account(), amount, KeepAlive)?;
+            <Bonds<T>>::mutate(&relayer, |bond| *bond = bond.saturating_add(amount));
+            <KnownRelayers<T>>::insert(&relayer, ());
+
+            Self::deposit_event(RawEvent::RelayerBonded(relayer, amount));
+            Ok(())
+        }
+
+        /// Unbonds the amount of the bond of a former relayer. The amount stays bonded for
+        /// [Trait::BondingDuration] blocks, unbonding more restarts the duration.
+        #[weight = T::DbWeight::get().reads_writes(3, 2) + 195_000_000]
+        pub fn unbond(origin, amount: BalanceOf<T>) -> DispatchResult {
+            let relayer = ensure_signed(origin)?;
+            ensure!(!<chainbridge::Module<T>>::is_relayer(&relayer), Error::<T>::StillRelayer);
+            let bond = Self::bond_of(&relayer);
+            ensure!(bond >= amount, Error::<T>::InsufficientBond);
+
+            let until = <system::Module<T>>::block_number().saturating_add(T::BondingDuration::get());
+            let unbonding = Self::unbonding_of(&relayer).map_or(amount, |(unbonding, _)| unbonding.saturating_add(amount));
+            <Unbonding<T>>::insert(&relayer, (unbonding, until));
+            Self::set_bond(&relayer, bond - amount);
+
+            Self::deposit_event(RawEvent::RelayerUnbonded(relayer, amount, until));
+            Ok(())
+        }
+
+        /// Pays the unbonded amount back to a former relayer once the bonding duration passed.
+        #[weight = T::DbWeight::get().reads_writes(2, 2) + 195_000_000]
+        pub fn withdraw_unbonded(origin) -> DispatchResult {
+            let relayer = ensure_signed(origin)?;
+            let (amount, until) = Self::unbonding_of(&relayer).ok_or(Error::<T>::NotUnbonding)?;
+            ensure!(<system::Module<T>>::block_number() >= until, Error::<T>::StillUnbonding);
+
+            T::Currency::transfer(&Self::bond_account(), &relayer, amount, AllowDeath)?;
+            <Unbonding<T>>::remove(&relayer);
+
+            Self::deposit_event(RawEvent::UnbondedWithdrawn(relayer, amount));
+            Ok(())
+        }
+
+        /// Records the votes of relayers on an executed proposal and pays the reward of the
+        /// proposal to the bonded relayers that voted for it. Can be called by anyone, once per
+        /// proposal. The votes are recorded even if the reward pot can not pay the rewards yet, in
+        /// which case the rewards can be paid by calling again later.
+        #[weight = 195_000_000]
+        pub fn reward_relayers(origin,
+                               src_id: chainbridge::ChainId,
+                               nonce: chainbridge::DepositNonce,
+                               proposal: Box<<T as chainbridge::Trait>::Proposal>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(!Self::rewarded(src_id, nonce), Error::<T>::AlreadyRewarded);
+            let votes = <chainbridge::Module<T>>::votes(src_id, (nonce, *proposal))
+                .ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(votes.status == chainbridge::ProposalStatus::Approved, Error::<T>::ProposalNotExecuted);
+
+            let incentives = Self::relayer_incentives();
+            let eligible = votes.votes_for.iter()
+                .filter(|relayer| Self::bond_of(*relayer) >= incentives.min_bond)
+                .collect::<Vec<_>>();
+            let reward = if eligible.is_empty() {
+                Zero::zero()
+            } else {
+                incentives.reward_per_proposal / (eligible.len() as u32).into()
+            };
+            let pot = Self::relayer_pot();
+            let total = reward.saturating_mul((eligible.len() as u32).into());
+
+            if !Self::recorded(src_id, nonce) {
+                for relayer in votes.votes_for.iter() {
+                    <Stats<T>>::mutate(relayer, |stats| stats.votes_for = stats.votes_for.saturating_add(1));
+                }
+                for relayer in votes.votes_against.iter() {
+                    <Stats<T>>::mutate(relayer, |stats| stats.votes_against = stats.votes_against.saturating_add(1));
+                }
+                Recorded::insert(src_id, nonce, true);
+            }
+            if T::Currency::free_balance(&pot) < total {
+                Self::deposit_event(RawEvent::RelayerVotesRecorded(src_id, nonce));
+                return Ok(());
+            }
+
+            for relayer in eligible {
+                T::Currency::transfer(&pot, relayer, reward, AllowDeath)?;
+                <Stats<T>>::mutate(relayer, |stats| stats.rewarded = stats.rewarded.saturating_add(reward));
+            }
+            Rewarded::insert(src_id, nonce, true);
+
+            Self::deposit_event(RawEvent::RelayersRewarded(src_id, nonce, reward));
+            Ok(())
+        }
+
+        /// Burns some amount of a fungible asset, e.g. a bridged ERC-20 token, and transfers it to
+        /// some recipient on a (whitelisted) destination chain. Fees are paid in the native token,
+        /// so only the flat part of a bridge fee applies.
+        #[weight = <fees::Module<T>>::charge_weight().saturating_add(195_000_000)]
+        pub fn transfer_asset_fungible(origin,
+                                       recipient: Vec<u8>,
+                                       asset_id: assets::AssetId,
+                                       amount: <T as assets::Trait>::Balance,
+                                       dest_id: chainbridge::ChainId,
+        ) -> DispatchResult {
+            let source = ensure_signed(origin)?;
+            ensure!(<chainbridge::Module<T>>::chain_whitelisted(dest_id), Error::<T>::InvalidTransfer);
+            Self::ensure_not_paused(Direction::Outgoing)?;
+
+            let resource_id: ResourceId = <bridge_mapping::Module<T>>::resource_of_asset(asset_id)
+                .ok_or(Error::<T>::ResourceIdDoesNotExist)?
+                .into();
+            Self::ensure_transferable(resource_id, dest_id)?;
+            ensure!(<assets::Module<T>>::balance_of(asset_id, &source) >= amount, assets::Error::<T>::InsufficientBalance);
+
+            // Pay additional fees
+            let token_fee = Self::quote(FeeKey::FungibleBridge, dest_id, resource_id, Zero::zero())
+                .ok_or(Error::<T>::FeeNotSet)?;
+            Self::pay_bridge_fee(&source, FeeKey::FungibleBridge, token_fee)?;
+
+            <assets::Module<T>>::burn(asset_id, &source, amount)?;
+            <chainbridge::Module<T>>::transfer_fungible(dest_id, resource_id, recipient.clone(), U256::from(amount.saturated_into::<u128>()))?;
+
+            let nonce = <chainbridge::Module<T>>::deposit_counts(dest_id);
+            Self::deposit_event(RawEvent::FungibleAssetTransferSent(source, recipient, asset_id, amount, token_fee, dest_id, resource_id, nonce));
+            Ok(())
         }
 
         //
         // Executable calls. These can be triggered by a chainbridge transfer initiated on another chain
         //
 
-        /// Executes a simple currency transfer using the chainbridge account as the source
+        /// Executes a simple currency transfer using the chainbridge account as the source. Amounts
+        /// of a resource mapped to a fungible asset are minted to the recipient instead.
         #[weight = 195_000_000]
         pub fn transfer(origin, to: T::AccountId, amount: BalanceOf<T>, r_id: ResourceId) -> DispatchResult {
             let source = T::BridgeOrigin::ensure_origin(origin)?;
-            T::Currency::transfer(&source, &to, amount.into(), AllowDeath)?;
+            Self::ensure_not_paused(Direction::Incoming)?;
+
+            let rid: <T as bridge_mapping::Trait>::ResourceId = r_id.into();
+            match <bridge_mapping::Module<T>>::asset_of(rid) {
+                Some(asset_id) => {
+                    let amount = amount.saturated_into::<u128>().saturated_into();
+                    <assets::Module<T>>::mint(asset_id, &to, amount)?;
+                    Self::deposit_event(RawEvent::FungibleAssetTransferReceived(to, asset_id, amount, r_id));
+                }
+                None => {
+                    T::Currency::transfer(&source, &to, amount.into(), AllowDeath)?;
+                    Self::deposit_event(RawEvent::NativeTransferReceived(to, amount, r_id));
+                }
+            }
             Ok(())
         }
 
-        #[weight = 195_000_000]
+        /// Releases a locked nft to the recipient. An nft that originates on another chain is
+        /// minted into the mapped registry with the received metadata on its first receipt, if
+        /// the metadata of the resource marks it as foreign.
+        #[weight = T::DbWeight::get().reads_writes(5, 3)
+            .saturating_add((metadata.len() as Weight).saturating_mul(nft::WEIGHT_PER_METADATA_BYTE))
+            .saturating_add(195_000_000)]
         pub fn receive_nonfungible(origin,
                                    to: T::AccountId,
                                    token_id: TokenId,
-                                   _metadata: Vec<u8>,
+                                   metadata: Vec<u8>,
                                    resource_id: ResourceId
         ) -> DispatchResult {
             let source = T::BridgeOrigin::ensure_origin(origin)?;
+            Self::ensure_not_paused(Direction::Incoming)?;
 
             // Get registry from resource id
             let rid: <T as bridge_mapping::Trait>::ResourceId = resource_id.into();
-            let registry_id = <bridge_mapping::Module<T>>::addr_of(rid)
+            let registry_id = <bridge_mapping::Module<T>>::addr_of(&rid)
                 .ok_or(Error::<T>::RegistryIdDoesNotExist)?;
             let registry_id: Address = registry_id.into().into();
 
-            // Transfer from bridge account to destination account
             let asset_id = AssetId(registry_id.into(), token_id);
-            <nft::Module<T> as Unique>::transfer(&source, &to, &asset_id)
+            if <nft::Module<T> as Unique>::owner_of(&asset_id).is_some() {
+                // Transfer from bridge account to destination account
+                <nft::Module<T>>::unlock(&source, &to, &asset_id)?;
+            } else {
+                // Wrap an nft of another chain, which never mints into registries of this chain
+                let foreign = <bridge_mapping::Module<T>>::metadata_of(rid)
+                    .map_or(false, |m| m.kind == bridge_mapping::ResourceKind::ForeignNft);
+                ensure!(foreign, Error::<T>::NotForeignResource);
+                <nft::Module<T> as Mintable>::mint(&source, &to, &asset_id, AssetInfo { metadata })?;
+            }
+
+            Self::deposit_event(RawEvent::AssetTransferReceived(to, asset_id, resource_id));
+            Ok(())
         }
 
         /// This can be called by the chainbridge to demonstrate an arbitrary call from a proposal.
@@ -219,19 +683,373 @@ decl_module! {
             Ok(())
         }
 
-        /// update token transfer fee
+        //
+        // Admin calls
+        //
+
+        /// Sets or removes the limits of native token transfers to the destination chain.
         #[weight = 195_000_000]
-		pub fn set_token_transfer_fee(origin, fee: T::Balance) -> DispatchResult {
-			Self::ensure_admin(origin)?;
-			TokenTransferFee::<T>::mutate(|transfer_token_fee| {
-				*transfer_token_fee = fee
-			});
-			Ok(())
-		}
+        pub fn set_transfer_limits(origin,
+                                   dest_id: chainbridge::ChainId,
+                                   limits: Option<TransferLimits<BalanceOf<T>, T::BlockNumber>>,
+        ) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            match &limits {
+                Some(l) => {
+                    ensure!(l.max_per_period.is_none() || !l.period.is_zero(), Error::<T>::InvalidLimits);
+                    <Limits<T>>::insert(dest_id, l);
+                }
+                None => <Limits<T>>::remove(dest_id),
+            }
+            // Start a fresh period with the new limits
+            <Outflows<T>>::remove(dest_id);
+
+            Self::deposit_event(RawEvent::TransferLimitsSet(dest_id, limits));
+            Ok(())
+        }
+
+        /// Sets or removes the fee of transfers of the resource to the destination chain.
+        #[weight = 195_000_000]
+        pub fn set_bridge_fee(origin,
+                              dest_id: chainbridge::ChainId,
+                              resource_id: ResourceId,
+                              fee: Option<BridgeFee<BalanceOf<T>>>,
+        ) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            match &fee {
+                Some(f) => {
+                    ensure!(f.bps <= MAX_BPS, Error::<T>::InvalidFee);
+                    <BridgeFees<T>>::insert(dest_id, resource_id, f);
+                }
+                None => <BridgeFees<T>>::remove(dest_id, resource_id),
+            }
+
+            Self::deposit_event(RawEvent::BridgeFeeSet(dest_id, resource_id, fee));
+            Ok(())
+        }
+
+        /// Sets or removes the account receiving bridge fees.
+        #[weight = 195_000_000]
+        pub fn set_fee_account(origin, account: Option<T::AccountId>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            match &account {
+                Some(a) => <FeeAccount<T>>::put(a),
+                None => <FeeAccount<T>>::kill(),
+            }
+
+            Self::deposit_event(RawEvent::FeeAccountSet(account));
+            Ok(())
+        }
+
+        /// Sets the incentives of relayers.
+        #[weight = 195_000_000]
+        pub fn set_relayer_incentives(origin, incentives: RelayerIncentives<BalanceOf<T>>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            Incentives::<T>::put(&incentives);
+
+            Self::deposit_event(RawEvent::RelayerIncentivesSet(incentives));
+            Ok(())
+        }
+
+        /// Slashes the amount from the bond of the relayer, e.g. for voting on an invalid
+        /// proposal, and from its unbonding amount if the bond does not cover it. The slashed
+        /// amount is burned.
+        #[weight = T::DbWeight::get().reads_writes(4, 4) + 195_000_000]
+        pub fn slash_relayer(origin, relayer: T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            let bond = Self::bond_of(&relayer);
+            let unbonding = Self::unbonding_of(&relayer);
+            let unbonding_amount = unbonding.as_ref().map_or(Zero::zero(), |(unbonding, _)| *unbonding);
+            ensure!(bond.saturating_add(unbonding_amount) >= amount, Error::<T>::InsufficientBond);
+
+            // Only the bond account is slashed, which holds no more than the bonds
+            let (burned, remaining) = T::Currency::slash(&Self::bond_account(), amount);
+            drop(burned);
+            let slashed = amount - remaining;
+            let from_bond = slashed.min(bond);
+            Self::set_bond(&relayer, bond - from_bond);
+            if let Some((unbonding, until)) = unbonding {
+                let unbonding = unbonding - (slashed - from_bond);
+                if unbonding.is_zero() {
+                    <Unbonding<T>>::remove(&relayer);
+                } else {
+                    <Unbonding<T>>::insert(&relayer, (unbonding, until));
+                }
+            }
+            <Stats<T>>::mutate(&relayer, |stats| stats.slashed = stats.slashed.saturating_add(slashed));
+
+            Self::deposit_event(RawEvent::RelayerSlashed(relayer, slashed));
+            Ok(())
+        }
+
+        /// Rejects all transfers in the direction until unpaused.
+        #[weight = 195_000_000]
+        pub fn pause(origin, direction: Direction) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            Paused::insert(direction, true);
+
+            Self::deposit_event(RawEvent::Paused(direction));
+            Ok(())
+        }
+
+        /// Accepts transfers in the direction again.
+        #[weight = 195_000_000]
+        pub fn unpause(origin, direction: Direction) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            Paused::remove(direction);
+
+            Self::deposit_event(RawEvent::Unpaused(direction));
+            Ok(())
+        }
+
+        /// Adds a relayer to the chainbridge and the relayers known to this module.
+        #[weight = T::DbWeight::get().reads_writes(1, 3) + 195_000_000]
+        pub fn add_relayer(origin, relayer: T::AccountId) -> DispatchResult {
+            Self::ensure_chainbridge_admin(origin)?;
+            <chainbridge::Module<T>>::register_relayer(relayer.clone())?;
+            <KnownRelayers<T>>::insert(&relayer, ());
+            Ok(())
+        }
+
+        /// Removes a relayer from the chainbridge and the relayers known to this module.
+        #[weight = T::DbWeight::get().reads_writes(1, 3) + 195_000_000]
+        pub fn remove_relayer(origin, relayer: T::AccountId) -> DispatchResult {
+            Self::ensure_chainbridge_admin(origin)?;
+            <chainbridge::Module<T>>::unregister_relayer(relayer.clone())?;
+            <KnownRelayers<T>>::remove(&relayer);
+            Ok(())
+        }
+
+        /// Whitelists a chain with the chainbridge and the chains known to this module.
+        #[weight = T::DbWeight::get().reads_writes(2, 2) + 195_000_000]
+        pub fn whitelist_chain(origin, chain_id: chainbridge::ChainId) -> DispatchResult {
+            Self::ensure_chainbridge_admin(origin)?;
+            <chainbridge::Module<T>>::whitelist(chain_id)?;
+            WhitelistedChains::mutate(|chains| chains.push(chain_id));
+            Ok(())
+        }
     }
 }
 
 impl<T: Trait> Module<T> {
+    /// Ensure that the given origin is either root or the [AdminOrigin]
+    fn ensure_admin(o: T::Origin) -> DispatchResult {
+        <T as Trait>::AdminOrigin::try_origin(o)
+            .map(|_| ())
+            .or_else(ensure_root)?;
+        Ok(())
+    }
+
+    /// Relayers and chains are managed by the admin of the chainbridge
+    fn ensure_chainbridge_admin(o: T::Origin) -> DispatchResult {
+        <T as chainbridge::Trait>::AdminOrigin::try_origin(o)
+            .map(|_| ())
+            .or_else(ensure_root)?;
+        Ok(())
+    }
+
+    /// Returns the fee of a transfer of the amount of the resource to the destination chain.
+    /// Falls back to the price of the fee key if no bridge fee is set.
+    pub fn quote(
+        key: FeeKey,
+        dest_id: chainbridge::ChainId,
+        resource_id: ResourceId,
+        amount: BalanceOf<T>,
+    ) -> Option<BalanceOf<T>> {
+        match Self::bridge_fee_of(dest_id, resource_id) {
+            Some(fee) => Some(fee.of(amount)),
+            None => Self::price_of(key),
+        }
+    }
+
+    /// Returns the fee of a native token transfer of the amount to the destination chain
+    pub fn quote_native(dest_id: chainbridge::ChainId, amount: BalanceOf<T>) -> Option<BalanceOf<T>> {
+        Self::quote(FeeKey::NativeBridge, dest_id, T::NativeTokenId::get(), amount)
+    }
+
+    /// Returns the fee of a transfer of an nft of the registry to the destination chain
+    pub fn quote_nft(registry_id: RegistryId, dest_id: chainbridge::ChainId) -> Option<BalanceOf<T>> {
+        match Self::resource_id_of(registry_id) {
+            Some(resource_id) => Self::quote(FeeKey::NftBridge, dest_id, resource_id, Zero::zero()),
+            None => Self::price_of(FeeKey::NftBridge),
+        }
+    }
+
+    /// Returns the fee of a transfer of the fungible asset to the destination chain. Only the flat
+    /// part of a bridge fee applies.
+    pub fn quote_fungible(asset_id: assets::AssetId, dest_id: chainbridge::ChainId) -> Option<BalanceOf<T>> {
+        match <bridge_mapping::Module<T>>::resource_of_asset(asset_id) {
+            Some(resource_id) => Self::quote(FeeKey::FungibleBridge, dest_id, resource_id.into(), Zero::zero()),
+            None => Self::price_of(FeeKey::FungibleBridge),
+        }
+    }
+
+    fn price_of(key: FeeKey) -> Option<BalanceOf<T>> {
+        <fees::Module<T>>::price_of(key)
+            .map(|fee| fee.saturated_into::<u128>().saturated_into())
+    }
+
+    /// Returns the ids of all whitelisted chains
+    pub fn chains() -> Vec<chainbridge::ChainId> {
+        let mut chains = WhitelistedChains::get();
+        chains.sort();
+        chains
+    }
+
+    /// Returns the known relayers that are still relayers, along with the size of the whole
+    /// relayer set and the relayer threshold. The count includes relayers added to the
+    /// chainbridge directly, which are not known.
+    pub fn relayers() -> RelayerSet<T::AccountId> {
+        RelayerSet {
+            relayers: <KnownRelayers<T>>::iter()
+                .map(|(relayer, _)| relayer)
+                .filter(|relayer| <chainbridge::Module<T>>::is_relayer(relayer))
+                .collect(),
+            count: <chainbridge::Module<T>>::relayer_count(),
+            threshold: <chainbridge::Module<T>>::relayer_threshold(),
+        }
+    }
+
+    /// Returns every resource of the bridge, i.e. the hash and native token resources and every
+    /// resource of the bridge mapping, ordered by resource id
+    pub fn resources() -> Vec<ResourceInfo> {
+        let mut resources = BTreeMap::new();
+        for resource_id in vec![T::HashId::get(), T::NativeTokenId::get()] {
+            Self::resource_info(&mut resources, resource_id);
+        }
+        for (resource_id, address) in <bridge_mapping::Module<T>>::addresses() {
+            let address: Bytes32 = address.into();
+            Self::resource_info(&mut resources, resource_id.into()).address = Some(address.into());
+        }
+        for (resource_id, asset_id) in <bridge_mapping::Module<T>>::assets() {
+            Self::resource_info(&mut resources, resource_id.into()).asset_id = Some(asset_id);
+        }
+        for (resource_id, metadata) in <bridge_mapping::Module<T>>::metadata() {
+            Self::resource_info(&mut resources, resource_id.into()).metadata = Some(metadata);
+        }
+
+        resources.into_iter().map(|(_, info)| info).collect()
+    }
+
+    fn resource_info(
+        resources: &mut BTreeMap<ResourceId, ResourceInfo>,
+        resource_id: ResourceId,
+    ) -> &mut ResourceInfo {
+        resources.entry(resource_id).or_insert_with(|| ResourceInfo {
+            resource_id,
+            method: <chainbridge::Module<T>>::resources(resource_id),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the fees of native token, nft and fungible asset transfers and every bridge fee
+    pub fn fee_schedule() -> FeeSchedule<BalanceOf<T>> {
+        FeeSchedule {
+            native: Self::price_of(FeeKey::NativeBridge),
+            nft: Self::price_of(FeeKey::NftBridge),
+            fungible: Self::price_of(FeeKey::FungibleBridge),
+            bridge_fees: <BridgeFees<T>>::iter().collect(),
+        }
+    }
+
+    /// Returns the resource id the registry is mapped to
+    pub fn resource_id_of(registry_id: RegistryId) -> Option<ResourceId> {
+        let reg: Address = registry_id.into();
+        let reg: Bytes32 = reg.into();
+        let reg: <T as bridge_mapping::Trait>::Address = reg.into();
+        <bridge_mapping::Module<T>>::name_of(reg).map(Into::into)
+    }
+
+    /// Pays the share of relayers into the relayer reward pot, and the rest of the fee to the
+    /// fee account, or charges it as protocol fee of the key if no fee account is set.
+    fn pay_bridge_fee(source: &T::AccountId, key: FeeKey, fee: BalanceOf<T>) -> DispatchResult {
+        let relayer_share = Self::relayer_incentives().fee_share * fee;
+        if !relayer_share.is_zero() {
+            let share = T::Currency::withdraw(source, relayer_share, WithdrawReason::Fee.into(), KeepAlive)?;
+            T::Currency::resolve_creating(&Self::relayer_pot(), share);
+        }
+
+        let fee = fee - relayer_share;
+        match Self::fee_account() {
+            Some(account) => T::Currency::transfer(source, &account, fee, KeepAlive),
+            None => <fees::Module<T>>::charge(source, key, fee.saturated_into::<u128>().saturated_into()),
+        }
+    }
+
+    /// Account that pays the rewards of relayers
+    pub fn relayer_pot() -> T::AccountId {
+        RELAYER_POT_ID.into_account()
+    }
+
+    /// Account holding the bonds of relayers
+    pub fn bond_account() -> T::AccountId {
+        RELAYER_BONDS_ID.into_account()
+    }
+
+    fn set_bond(relayer: &T::AccountId, bond: BalanceOf<T>) {
+        if bond.is_zero() {
+            <Bonds<T>>::remove(relayer);
+        } else {
+            <Bonds<T>>::insert(relayer, bond);
+        }
+    }
+
+    /// Ensure that the resource is enabled and allowed to be transferred to the chain
+    fn ensure_transferable(resource_id: ResourceId, dest_id: chainbridge::ChainId) -> DispatchResult {
+        let rid: <T as bridge_mapping::Trait>::ResourceId = resource_id.into();
+        ensure!(<bridge_mapping::Module<T>>::is_transferable(&rid, dest_id), Error::<T>::ResourceNotTransferable);
+        Ok(())
+    }
+
+    fn ensure_not_paused(direction: Direction) -> DispatchResult {
+        ensure!(!Self::paused(direction), Error::<T>::TransfersPaused);
+        Ok(())
+    }
+
+    /// Checks the amount against the limits of the destination chain. Returns the outflow
+    /// including the amount, to be stored once the transfer succeeded, if the outflow of the chain
+    /// is capped. The outflow is tracked in up to [OUTFLOW_BUCKETS] buckets of consecutive blocks
+    /// per period. The whole bucket containing the first block of the period ending now counts
+    /// towards the cap, so that the cap holds for any period.
+    fn outflow_after(
+        dest_id: chainbridge::ChainId,
+        amount: BalanceOf<T>,
+    ) -> Result<Option<Vec<(T::BlockNumber, BalanceOf<T>)>>, Error<T>> {
+        let limits = match Self::limits_of(dest_id) {
+            Some(limits) => limits,
+            None => return Ok(None),
+        };
+        if let Some(max) = limits.max_per_transfer {
+            ensure!(amount <= max, Error::<T>::TransferTooLarge);
+        }
+
+        let cap = match limits.max_per_period {
+            Some(cap) => cap,
+            None => return Ok(None),
+        };
+        let now = <system::Module<T>>::block_number();
+        let one: T::BlockNumber = 1u32.into();
+        let buckets: T::BlockNumber = OUTFLOW_BUCKETS.into();
+        let bucket_length = (limits.period + buckets - one) / buckets;
+        let bucket_of = |block: T::BlockNumber| block - block % bucket_length;
+
+        // Forget the buckets that ended before the period ending now
+        let first = bucket_of(now.saturating_sub(limits.period - one));
+        let mut outflows = Self::outflow_of(dest_id);
+        outflows.retain(|(bucket, _)| *bucket >= first);
+
+        let outflow = outflows.iter()
+            .fold(amount, |total, (_, outflow)| total.saturating_add(*outflow));
+        ensure!(outflow <= cap, Error::<T>::OutflowCapExceeded);
+
+        let current = bucket_of(now);
+        match outflows.last_mut() {
+            Some((bucket, outflow)) if *bucket == current => *outflow = outflow.saturating_add(amount),
+            _ => outflows.push((current, amount)),
+        }
+        Ok(Some(outflows))
+    }
+
     /// Its called as part of genesis step to initialize some dev parameters
     fn initialize(
         chains: &[u8],
@@ -240,23 +1058,19 @@ impl<T: Trait> Module<T> {
         threshold: &u32,
     ) {
         chains.into_iter().for_each(|c| {
-            <chainbridge::Module<T>>::whitelist(*c).unwrap_or_default();
+            if <chainbridge::Module<T>>::whitelist(*c).is_ok() {
+                WhitelistedChains::append(c);
+            }
         });
         relayers.into_iter().for_each(|rs| {
             <chainbridge::Module<T>>::register_relayer(rs.clone()).unwrap_or_default();
+            <KnownRelayers<T>>::insert(rs, ());
         });
         <chainbridge::Module<T>>::set_relayer_threshold(*threshold).unwrap_or_default();
         for &(ref re, ref m) in resources.iter() {
             <chainbridge::Module<T>>::register_resource(*re, m.clone()).unwrap_or_default();
         }
     }
-
-	fn ensure_admin(o: T::Origin) -> DispatchResult {
-		<T as Trait>::AdminOrigin::try_origin(o)
-			.map(|_| ())
-			.or_else(ensure_root)?;
-		Ok(())
-	}
 }
 
 #[cfg(test)]
@@ -274,6 +1088,7 @@ mod tests{
 		traits::{AccountIdConversion, BlakeTwo256, Hash, Block as BlockT, IdentityLookup}, ModuleId, Perbill,
 	};
 	use crate::bridge as pallet_bridge;
+	use crate::constants::currency;
     use crate::{nft, va_registry as registry};
 
 	pub use pallet_balances as balances;
@@ -336,6 +1151,7 @@ mod tests{
 	parameter_types! {
 		pub const TestChainId: u8 = 5;
 		pub const ProposalLifetime: u64 = 10;
+		pub const BondingDuration: u64 = 10;
 	}
 
 	impl chainbridge::Trait for Test {
@@ -349,6 +1165,13 @@ mod tests{
 	impl fees::Trait for Test {
 		type Event = Event;
 		type FeeChangeOrigin = frame_system::EnsureRoot<u64>;
+		type Author = ();
+		type TreasuryAccount = ();
+		type StakingPotAccount = ();
+		type CreditAccount = ();
+		type SpendOrigin = frame_system::EnsureRoot<u64>;
+		type MaxFeeHistory = ();
+		type CfgPrice = ();
 	}
 
 	impl pallet_authorship::Trait for Test {
@@ -358,14 +1181,26 @@ mod tests{
 		type EventHandler = ();
 	}
 
+    parameter_types! {
+        pub const MaxMetadataLength: u32 = 1024;
+    }
     impl nft::Trait for Test {
         type Event = Event;
         type AssetInfo = registry::types::AssetInfo;
+        type MaxMetadataLength = MaxMetadataLength;
     }
 
     impl bridge_mapping::Trait for Test {
+        type Event = Event;
         type ResourceId = ResourceId;
         type Address = Address;
+        type AssetId = crate::assets::AssetId;
+        type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    }
+
+    impl crate::assets::Trait for Test {
+        type Event = Event;
+        type Balance = u128;
         type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
     }
 
@@ -397,6 +1232,7 @@ mod tests{
 		type HashId = HashId;
 		type NativeTokenId = NativeTokenId;
 		type AdminOrigin = EnsureSignedBy<One, u64>;
+		type BondingDuration = BondingDuration;
 	}
 
 	pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
@@ -415,6 +1251,8 @@ mod tests{
 			Fees: fees::{Module, Call, Event<T>},
             Nft: nft::{Module, Event<T>},
             Registry: registry::{Module, Call, Event<T>},
+            Assets: assets::{Module, Call, Event<T>},
+            BridgeMapping: bridge_mapping::{Module, Call, Event<T>},
 		}
 	);
 
@@ -438,15 +1276,18 @@ mod tests{
             .assimilate_storage(&mut t)
             .unwrap();
         fees::GenesisConfig::<Test> {
-            initial_fees: vec![(
-                // anchoring state rent fee per day
-                H256::from(&[
-                    17, 218, 109, 31, 118, 29, 223, 155, 219, 76, 157, 110, 83, 3, 235, 212, 31, 97,
-                    133, 141, 10, 86, 71, 161, 167, 191, 224, 137, 191, 146, 27, 233,
-                ]),
-                // state rent 0 for tests
-                0,
-            )]}
+            destinations: vec![],
+            initial_fees: vec![
+                (
+                    // anchoring state rent fee per day
+                    FeeKey::AnchorRentPerDay,
+                    // state rent 0 for tests
+                    0,
+                ),
+                (FeeKey::NftBridge, 20 * currency::CFG),
+                (FeeKey::NativeBridge, 2000 * currency::CFG),
+                (FeeKey::FungibleBridge, 30 * currency::CFG),
+            ]}
             .assimilate_storage(&mut t)
             .unwrap();
         let mut ext = sp_io::TestExternalities::new(t);
@@ -554,13 +1395,24 @@ mod tests{
 				dest_chain,
 			));
 
-			expect_event(chainbridge::RawEvent::FungibleTransfer(
-				dest_chain,
-				1,
-				resource_id,
-				amount.into(),
-				recipient,
-			));
+			assert_events(vec![
+				Event::chainbridge(chainbridge::RawEvent::FungibleTransfer(
+					dest_chain,
+					1,
+					resource_id,
+					amount.into(),
+					recipient.clone(),
+				)),
+				Event::pallet_bridge(RawEvent::NativeTransferSent(
+					RELAYER_A,
+					recipient,
+					amount,
+					2000 * currency::CFG,
+					dest_chain,
+					resource_id,
+					1,
+				)),
+			]);
 
 			// Account balance should be reduced amount + fee
 			account_current_balance = <pallet_balances::Module<Test>>::free_balance(RELAYER_A);
@@ -605,8 +1457,8 @@ mod tests{
         // Register resource with chainbridge
         assert_ok!(<chainbridge::Module<Test>>::register_resource(resource_id.clone(), vec![]));
         // Register resource in local resource mapping
-        <bridge_mapping::Module<Test>>::set_resource(resource_id.clone(),
-                                                   registry_id.clone().into());
+        assert_ok!(<bridge_mapping::Module<Test>>::set_resource(resource_id.clone(),
+                                                              registry_id.clone().into()));
 
         registry_id
     }
@@ -637,6 +1489,96 @@ mod tests{
             // Recipient owns the nft now
             assert_eq!(<crate::nft::Module<Test>>::account_for_asset(registry_id, token_id),
                        Some(recipient));
+            expect_event(Event::pallet_bridge(RawEvent::AssetTransferReceived(
+                recipient,
+                AssetId(registry_id, token_id),
+                resource_id,
+            )));
+        })
+    }
+
+    #[test]
+    fn receive_nonfungible_mints_wrapped_asset() {
+        new_test_ext().execute_with(|| {
+            let resource_id = chainbridge::derive_resource_id(1, &blake2_128(b"tinlake"));
+            let registry_id = RegistryId::from_low_u64_be(7);
+            let recipient = RELAYER_A;
+            let origin = Origin::signed(<chainbridge::Module<Test>>::account_id());
+            let token_id = U256::from(42);
+            let metadata = b"ipfs://tinlake-loan".to_vec();
+            assert_ok!(<bridge_mapping::Module<Test>>::set_resource(resource_id, Address::from(registry_id)));
+
+            // Only resources marked as foreign are minted
+            assert_noop!(
+                <Module<Test>>::receive_nonfungible(origin.clone(), recipient, token_id, metadata.clone(), resource_id),
+                Error::<Test>::NotForeignResource
+            );
+            assert_ok!(BridgeMapping::set_metadata(Origin::root(), resource_id, bridge_mapping::ResourceMetadata {
+                kind: bridge_mapping::ResourceKind::ForeignNft,
+                decimals: 0,
+                allowed_chains: vec![],
+                enabled: true,
+            }));
+            assert_noop!(
+                <Module<Test>>::receive_nonfungible(origin.clone(), recipient, token_id, vec![0; 1024], resource_id),
+                crate::nft::Error::<Test>::MetadataTooLong
+            );
+
+            // The asset has never been on this chain, so it is minted with the received metadata
+            assert_ok!(<Module<Test>>::receive_nonfungible(origin.clone(),
+                                                           recipient,
+                                                           token_id,
+                                                           metadata.clone(),
+                                                           resource_id));
+            assert_eq!(<crate::nft::Module<Test>>::account_for_asset(registry_id, token_id),
+                       Some(recipient));
+            assert_eq!(<crate::nft::Module<Test>>::asset(registry_id, token_id),
+                       Some(registry::types::AssetInfo { metadata: metadata.clone() }));
+
+            // Sending it back locks it, and receiving it again unlocks it
+            assert_ok!(ChainBridge::whitelist_chain(Origin::root(), 0));
+            assert_ok!(PalletBridge::transfer_asset(Origin::signed(recipient), vec![1], registry_id, token_id, 0));
+            assert_eq!(<crate::nft::Module<Test>>::account_for_asset(registry_id, token_id),
+                       Some(<chainbridge::Module<Test>>::account_id()));
+            assert_ok!(<Module<Test>>::receive_nonfungible(origin, RELAYER_B, token_id, vec![], resource_id));
+            assert_eq!(<crate::nft::Module<Test>>::account_for_asset(registry_id, token_id),
+                       Some(RELAYER_B));
+            assert_eq!(<crate::nft::Module<Test>>::asset(registry_id, token_id),
+                       Some(registry::types::AssetInfo { metadata }));
+        })
+    }
+
+    #[test]
+    fn disabled_resources_are_not_transferred() {
+        new_test_ext().execute_with(|| {
+            let dest_chain = 0;
+            let resource_id = NativeTokenId::get();
+            let owner = RELAYER_A;
+            let token_id = U256::one();
+            let registry_id = setup_nft(owner, token_id, resource_id);
+            assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+
+            let metadata = bridge_mapping::ResourceMetadata {
+                kind: bridge_mapping::ResourceKind::Nft,
+                decimals: 0,
+                allowed_chains: vec![1],
+                enabled: true,
+            };
+            assert_ok!(BridgeMapping::set_metadata(Origin::root(), resource_id, metadata));
+            assert_noop!(
+                PalletBridge::transfer_asset(Origin::signed(owner), vec![1], registry_id, token_id, dest_chain),
+                Error::<Test>::ResourceNotTransferable
+            );
+
+            assert_ok!(ChainBridge::whitelist_chain(Origin::root(), 1));
+            assert_ok!(BridgeMapping::set_enabled(Origin::root(), resource_id, false));
+            assert_noop!(
+                PalletBridge::transfer_asset(Origin::signed(owner), vec![1], registry_id, token_id, 1),
+                Error::<Test>::ResourceNotTransferable
+            );
+
+            assert_ok!(BridgeMapping::set_enabled(Origin::root(), resource_id, true));
+            assert_ok!(PalletBridge::transfer_asset(Origin::signed(owner), vec![1], registry_id, token_id, 1));
         })
     }
 
@@ -687,17 +1629,30 @@ mod tests{
             assert_eq!(<crate::nft::Module<Test>>::account_for_asset(registry_id, token_id),
                        Some(<chainbridge::Module<Test>>::account_id()));
 
-            // Check that transfer event was emitted
+            // Check that transfer event was emitted with the metadata of the asset
             let tid: &mut [u8] = &mut[0; 32];
             token_id.to_big_endian(tid);
-            expect_event(chainbridge::RawEvent::NonFungibleTransfer(
-                dest_chain,
-                1,
-                resource_id,
-                tid.to_vec(),
-                recipient,
-                vec![],
-            ));
+            let metadata = <crate::nft::Module<Test>>::asset(registry_id, token_id).unwrap().metadata;
+            assert!(!metadata.is_empty());
+            assert_events(vec![
+                Event::chainbridge(chainbridge::RawEvent::NonFungibleTransfer(
+                    dest_chain,
+                    1,
+                    resource_id,
+                    tid.to_vec(),
+                    recipient.clone(),
+                    metadata,
+                )),
+                Event::pallet_bridge(RawEvent::AssetTransferSent(
+                    owner,
+                    recipient,
+                    AssetId(registry_id, token_id),
+                    20 * currency::CFG,
+                    dest_chain,
+                    resource_id,
+                    1,
+                )),
+            ]);
         })
     }
 
@@ -733,7 +1688,7 @@ mod tests{
 				Box::new(proposal.clone())
 			));
 
-			event_exists(RawEvent::Remark(hash, r_id));
+			event_exists(Event::pallet_bridge(RawEvent::Remark(hash, r_id)));
 		})
 	}
 
@@ -774,22 +1729,390 @@ mod tests{
 			assert_eq!(Balances::free_balance(&bridge_id), ENDOWED_BALANCE - 10);
 			assert_eq!(Balances::free_balance(RELAYER_A), ENDOWED_BALANCE + 10);
 
-			assert_events(vec![Event::balances(balances::RawEvent::Transfer(
-				ChainBridge::account_id(),
-				RELAYER_A,
-				10,
-			))]);
+			assert_events(vec![
+				Event::balances(balances::RawEvent::Transfer(
+					ChainBridge::account_id(),
+					RELAYER_A,
+					10,
+				)),
+				Event::pallet_bridge(RawEvent::NativeTransferReceived(RELAYER_A, 10, resource_id)),
+			]);
 		})
 	}
 
 	#[test]
 	fn update_transfer_token_fee() {
 		new_test_ext().execute_with(||{
-			let current_fee = PalletBridge::token_transfer_fee();
-			assert_eq!(current_fee, 2000 * currency::CFG);
+			let current_fee = Fees::price_of(FeeKey::NativeBridge);
+			assert_eq!(current_fee, Some(2000 * currency::CFG));
 			let new_fee = 3000 * currency::CFG;
-			assert_ok!(PalletBridge::set_token_transfer_fee(Origin::signed(1), new_fee));
-			assert_eq!(new_fee, PalletBridge::token_transfer_fee());
+			assert_ok!(Fees::set_fee(Origin::root(), FeeKey::NativeBridge, new_fee));
+			assert_eq!(Some(new_fee), Fees::price_of(FeeKey::NativeBridge));
+
+			// New fee is burned on transfer
+			let account = 2;
+			let _ = Balances::deposit_creating(&account, 5000 * currency::CFG);
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), 0));
+			assert_ok!(PalletBridge::transfer_native(Origin::signed(account), 10, vec![1], 0));
+			assert_eq!(Balances::free_balance(account), 2000 * currency::CFG - 10);
+		})
+	}
+
+	#[test]
+	fn transfer_native_limits() {
+		new_test_ext().execute_with(|| {
+			let dest_chain = 0;
+			let limits = TransferLimits {
+				max_per_transfer: Some(10),
+				max_per_period: Some(25),
+				period: 10,
+			};
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+			assert_ok!(Fees::set_fee(Origin::root(), FeeKey::NativeBridge, 0));
+			assert_noop!(
+				PalletBridge::set_transfer_limits(Origin::signed(RELAYER_A), dest_chain, Some(limits.clone())),
+				BadOrigin
+			);
+			assert_noop!(
+				PalletBridge::set_transfer_limits(Origin::root(), dest_chain, Some(TransferLimits { period: 0, ..limits.clone() })),
+				Error::<Test>::InvalidLimits
+			);
+			assert_ok!(PalletBridge::set_transfer_limits(Origin::signed(One::get()), dest_chain, Some(limits.clone())));
+			expect_event(Event::pallet_bridge(RawEvent::TransferLimitsSet(dest_chain, Some(limits))));
+
+			let transfer = |amount| PalletBridge::transfer_native(Origin::signed(RELAYER_A), amount, vec![1], dest_chain);
+			assert_noop!(transfer(11), Error::<Test>::TransferTooLarge);
+			assert_ok!(transfer(10));
+			assert_ok!(transfer(10));
+			assert_noop!(transfer(6), Error::<Test>::OutflowCapExceeded);
+			assert_ok!(transfer(5));
+			assert_eq!(PalletBridge::outflow_of(dest_chain), vec![(1, 25)]);
+
+			// The outflow is available again a period later
+			System::set_block_number(11);
+			assert_ok!(transfer(10));
+			assert_eq!(PalletBridge::outflow_of(dest_chain), vec![(11, 10)]);
+
+			// Without limits any amount can be transferred
+			assert_ok!(PalletBridge::set_transfer_limits(Origin::root(), dest_chain, None));
+			assert_ok!(transfer(100));
+		})
+	}
+
+	#[test]
+	fn transfer_native_limits_roll() {
+		new_test_ext().execute_with(|| {
+			let dest_chain = 0;
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+			assert_ok!(Fees::set_fee(Origin::root(), FeeKey::NativeBridge, 0));
+			assert_ok!(PalletBridge::set_transfer_limits(Origin::root(), dest_chain, Some(TransferLimits {
+				max_per_transfer: None,
+				max_per_period: Some(20),
+				period: 10,
+			})));
+			let transfer = |amount| PalletBridge::transfer_native(Origin::signed(RELAYER_A), amount, vec![1], dest_chain);
+
+			System::set_block_number(1);
+			assert_ok!(transfer(15));
+			// The period ending at block 10 includes block 1
+			System::set_block_number(10);
+			assert_noop!(transfer(6), Error::<Test>::OutflowCapExceeded);
+			assert_ok!(transfer(5));
+			assert_eq!(PalletBridge::outflow_of(dest_chain), vec![(1, 15), (10, 5)]);
+
+			// The period ending at block 11 no longer does
+			System::set_block_number(11);
+			assert_noop!(transfer(16), Error::<Test>::OutflowCapExceeded);
+			assert_ok!(transfer(15));
+			assert_eq!(PalletBridge::outflow_of(dest_chain), vec![(10, 5), (11, 15)]);
+		})
+	}
+
+	#[test]
+	fn transfer_native_limits_bucket_outflow() {
+		new_test_ext().execute_with(|| {
+			let dest_chain = 0;
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+			assert_ok!(Fees::set_fee(Origin::root(), FeeKey::NativeBridge, 0));
+			// Buckets of 5 blocks
+			assert_ok!(PalletBridge::set_transfer_limits(Origin::root(), dest_chain, Some(TransferLimits {
+				max_per_transfer: None,
+				max_per_period: Some(20),
+				period: 5 * OUTFLOW_BUCKETS as u64,
+			})));
+			let transfer = |amount| PalletBridge::transfer_native(Origin::signed(RELAYER_A), amount, vec![1], dest_chain);
+
+			System::set_block_number(6);
+			assert_ok!(transfer(5));
+			System::set_block_number(9);
+			assert_ok!(transfer(5));
+			System::set_block_number(12);
+			assert_ok!(transfer(5));
+			assert_eq!(PalletBridge::outflow_of(dest_chain), vec![(5, 10), (10, 5)]);
+
+			// The bucket of block 6 still counts while the period starts within it
+			System::set_block_number(6 + 5 * OUTFLOW_BUCKETS as u64);
+			assert_noop!(transfer(6), Error::<Test>::OutflowCapExceeded);
+			System::set_block_number(10 + 5 * OUTFLOW_BUCKETS as u64);
+			assert_ok!(transfer(15));
+			assert_eq!(PalletBridge::outflow_of(dest_chain), vec![(10, 5), (130, 15)]);
+		})
+	}
+
+	#[test]
+	fn paused_transfers_are_rejected() {
+		new_test_ext().execute_with(|| {
+			let dest_chain = 0;
+			let resource_id = NativeTokenId::get();
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+
+			assert_noop!(PalletBridge::pause(Origin::signed(RELAYER_A), Direction::Outgoing), BadOrigin);
+			assert_ok!(PalletBridge::pause(Origin::root(), Direction::Outgoing));
+			expect_event(Event::pallet_bridge(RawEvent::Paused(Direction::Outgoing)));
+			assert_noop!(
+				PalletBridge::transfer_native(Origin::signed(RELAYER_A), 10, vec![1], dest_chain),
+				Error::<Test>::TransfersPaused
+			);
+			assert_noop!(
+				PalletBridge::transfer_asset(Origin::signed(RELAYER_A), vec![1], Default::default(), U256::one(), dest_chain),
+				Error::<Test>::TransfersPaused
+			);
+			// Incoming transfers are still accepted
+			assert_ok!(PalletBridge::transfer(Origin::signed(ChainBridge::account_id()), RELAYER_A, 10, resource_id));
+
+			assert_ok!(PalletBridge::pause(Origin::root(), Direction::Incoming));
+			assert_noop!(
+				PalletBridge::transfer(Origin::signed(ChainBridge::account_id()), RELAYER_A, 10, resource_id),
+				Error::<Test>::TransfersPaused
+			);
+			assert_noop!(
+				PalletBridge::receive_nonfungible(Origin::signed(ChainBridge::account_id()), RELAYER_A, U256::one(), vec![], resource_id),
+				Error::<Test>::TransfersPaused
+			);
+
+			assert_ok!(PalletBridge::unpause(Origin::root(), Direction::Outgoing));
+			expect_event(Event::pallet_bridge(RawEvent::Unpaused(Direction::Outgoing)));
+			assert_ok!(PalletBridge::transfer_native(Origin::signed(RELAYER_A), 10, vec![1], dest_chain));
+		})
+	}
+
+	#[test]
+	fn bridge_fees_per_chain_and_resource() {
+		new_test_ext().execute_with(|| {
+			let dest_chain = 0;
+			let resource_id = NativeTokenId::get();
+			let fee_account = 99;
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+
+			// Falls back to the fee of the fees module
+			assert_eq!(PalletBridge::quote(FeeKey::NativeBridge, dest_chain, resource_id, 100), Some(2000 * currency::CFG));
+
+			let fee = BridgeFee { flat: 10, bps: 250 };
+			assert_noop!(
+				PalletBridge::set_bridge_fee(Origin::signed(RELAYER_A), dest_chain, resource_id, Some(fee.clone())),
+				BadOrigin
+			);
+			assert_noop!(
+				PalletBridge::set_bridge_fee(Origin::root(), dest_chain, resource_id, Some(BridgeFee { flat: 0, bps: 10_001 })),
+				Error::<Test>::InvalidFee
+			);
+			assert_ok!(PalletBridge::set_bridge_fee(Origin::root(), dest_chain, resource_id, Some(fee.clone())));
+			expect_event(Event::pallet_bridge(RawEvent::BridgeFeeSet(dest_chain, resource_id, Some(fee))));
+
+			// 10 flat plus 2.5% of 1000
+			assert_eq!(PalletBridge::quote(FeeKey::NativeBridge, dest_chain, resource_id, 1000), Some(35));
+			// Other chains still pay the fee of the fees module
+			assert_eq!(PalletBridge::quote(FeeKey::NativeBridge, 1, resource_id, 1000), Some(2000 * currency::CFG));
+
+			assert_ok!(PalletBridge::set_fee_account(Origin::signed(One::get()), Some(fee_account)));
+			expect_event(Event::pallet_bridge(RawEvent::FeeAccountSet(Some(fee_account))));
+			assert_ok!(PalletBridge::transfer_native(Origin::signed(RELAYER_A), 1000, vec![1], dest_chain));
+			assert_eq!(Balances::free_balance(fee_account), 35);
+			assert_eq!(Balances::free_balance(RELAYER_A), ENDOWED_BALANCE - 1035);
+
+			assert_ok!(PalletBridge::set_bridge_fee(Origin::root(), dest_chain, resource_id, None));
+			assert_eq!(PalletBridge::quote(FeeKey::NativeBridge, dest_chain, resource_id, 1000), Some(2000 * currency::CFG));
+		})
+	}
+
+	#[test]
+	fn bridge_fungible_asset() {
+		new_test_ext().execute_with(|| {
+			let dest_chain = 0;
+			let dai: crate::assets::AssetId = 1;
+			let resource_id = chainbridge::derive_resource_id(1, &blake2_128(b"DAI"));
+			let recipient = vec![99];
+			let metadata = crate::assets::AssetMetadata { symbol: b"DAI".to_vec(), decimals: 18 };
+			assert_ok!(Assets::create(Origin::root(), dai, metadata));
+			assert_ok!(BridgeMapping::set_asset(Origin::root(), resource_id, dai));
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), dest_chain));
+
+			// Only the bridge can mint the asset
+			assert_noop!(PalletBridge::transfer(Origin::signed(RELAYER_A), RELAYER_A, 100, resource_id), BadOrigin);
+
+			// Receiving mints the asset instead of transferring native tokens
+			assert_ok!(PalletBridge::transfer(Origin::signed(ChainBridge::account_id()), RELAYER_A, 100, resource_id));
+			assert_eq!(Assets::balance_of(dai, RELAYER_A), 100);
+			assert_eq!(Balances::free_balance(ChainBridge::account_id()), ENDOWED_BALANCE);
+			assert_events(vec![
+				Event::assets(crate::assets::RawEvent::Minted(dai, RELAYER_A, 100)),
+				Event::pallet_bridge(RawEvent::FungibleAssetTransferReceived(RELAYER_A, dai, 100, resource_id)),
+			]);
+
+			assert_noop!(
+				PalletBridge::transfer_asset_fungible(Origin::signed(RELAYER_A), recipient.clone(), dai, 101, dest_chain),
+				crate::assets::Error::<Test>::InsufficientBalance
+			);
+			assert_noop!(
+				PalletBridge::transfer_asset_fungible(Origin::signed(RELAYER_A), recipient.clone(), 2, 10, dest_chain),
+				Error::<Test>::ResourceIdDoesNotExist
+			);
+
+			// Sending burns the asset
+			assert_ok!(PalletBridge::transfer_asset_fungible(Origin::signed(RELAYER_A), recipient.clone(), dai, 40, dest_chain));
+			assert_eq!(Assets::balance_of(dai, RELAYER_A), 60);
+			assert_eq!(Assets::total_supply(dai), 60);
+			assert_events(vec![
+				Event::chainbridge(chainbridge::RawEvent::FungibleTransfer(
+					dest_chain,
+					1,
+					resource_id,
+					40.into(),
+					recipient.clone(),
+				)),
+				Event::pallet_bridge(RawEvent::FungibleAssetTransferSent(
+					RELAYER_A,
+					recipient,
+					dai,
+					40,
+					30 * currency::CFG,
+					dest_chain,
+					resource_id,
+					1,
+				)),
+			]);
+		})
+	}
+
+	#[test]
+	fn relayers_are_rewarded_and_slashed() {
+		new_test_ext().execute_with(|| {
+			let prop_id = 1;
+			let src_id = 1;
+			let r_id = chainbridge::derive_resource_id(src_id, b"transfer");
+			let proposal = make_transfer_proposal(RELAYER_A, 10, r_id);
+			let pot = PalletBridge::relayer_pot();
+
+			assert_ok!(ChainBridge::set_threshold(Origin::root(), TEST_THRESHOLD));
+			assert_ok!(ChainBridge::add_relayer(Origin::root(), RELAYER_A));
+			assert_ok!(ChainBridge::add_relayer(Origin::root(), RELAYER_B));
+			assert_ok!(ChainBridge::add_relayer(Origin::root(), RELAYER_C));
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), src_id));
+			assert_ok!(ChainBridge::set_resource(Origin::root(), r_id, b"PalletBridge.transfer".to_vec()));
+
+			let incentives = RelayerIncentives {
+				fee_share: Perbill::from_percent(50),
+				reward_per_proposal: 1000,
+				min_bond: 100,
+			};
+			assert_noop!(PalletBridge::set_relayer_incentives(Origin::signed(RELAYER_A), incentives.clone()), BadOrigin);
+			assert_ok!(PalletBridge::set_relayer_incentives(Origin::root(), incentives));
+
+			// Half of the bridge fee is paid into the reward pot
+			assert_ok!(PalletBridge::transfer_native(Origin::signed(RELAYER_A), 10, vec![1], src_id));
+			assert_eq!(Balances::free_balance(pot), 1000 * currency::CFG);
+
+			assert_noop!(PalletBridge::bond(Origin::signed(5), 100), Error::<Test>::NotRelayer);
+			assert_ok!(PalletBridge::bond(Origin::signed(RELAYER_A), 100));
+			assert_eq!(Balances::free_balance(PalletBridge::bond_account()), 100);
+
+			// Proposal is executed with votes of A and C, B votes against
+			assert_ok!(ChainBridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+			assert_ok!(ChainBridge::reject_proposal(Origin::signed(RELAYER_B), prop_id, src_id, r_id, Box::new(proposal.clone())));
+			assert_ok!(ChainBridge::acknowledge_proposal(Origin::signed(RELAYER_C), prop_id, src_id, r_id, Box::new(proposal.clone())));
+
+			assert_noop!(
+				PalletBridge::reward_relayers(Origin::signed(5), src_id, prop_id + 1, Box::new(proposal.clone())),
+				Error::<Test>::ProposalNotFound
+			);
+			assert_ok!(PalletBridge::reward_relayers(Origin::signed(5), src_id, prop_id, Box::new(proposal.clone())));
+			expect_event(Event::pallet_bridge(RawEvent::RelayersRewarded(src_id, prop_id, 1000)));
+			assert_noop!(
+				PalletBridge::reward_relayers(Origin::signed(5), src_id, prop_id, Box::new(proposal.clone())),
+				Error::<Test>::AlreadyRewarded
+			);
+
+			// Only A is bonded, so only A is rewarded
+			assert_eq!(PalletBridge::relayer_stats(RELAYER_A), RelayerStats { votes_for: 1, votes_against: 0, rewarded: 1000, slashed: 0 });
+			assert_eq!(PalletBridge::relayer_stats(RELAYER_B), RelayerStats { votes_for: 0, votes_against: 1, rewarded: 0, slashed: 0 });
+			assert_eq!(PalletBridge::relayer_stats(RELAYER_C), RelayerStats { votes_for: 1, votes_against: 0, rewarded: 0, slashed: 0 });
+			assert_eq!(Balances::free_balance(pot), 1000 * currency::CFG - 1000);
+
+			// Slashing leaves reserves of the relayer held for other reasons
+			assert_ok!(Balances::reserve(&RELAYER_A, 500));
+			assert_noop!(PalletBridge::slash_relayer(Origin::root(), RELAYER_A, 101), Error::<Test>::InsufficientBond);
+			assert_ok!(PalletBridge::slash_relayer(Origin::root(), RELAYER_A, 60));
+			expect_event(Event::pallet_bridge(RawEvent::RelayerSlashed(RELAYER_A, 60)));
+			assert_eq!(PalletBridge::bond_of(RELAYER_A), 40);
+			assert_eq!(PalletBridge::relayer_stats(RELAYER_A).slashed, 60);
+			assert_eq!(Balances::free_balance(PalletBridge::bond_account()), 40);
+			assert_eq!(Balances::reserved_balance(RELAYER_A), 500);
+
+			// Relayers can only unbond once removed
+			assert_noop!(PalletBridge::unbond(Origin::signed(RELAYER_A), 40), Error::<Test>::StillRelayer);
+			assert_ok!(ChainBridge::remove_relayer(Origin::root(), RELAYER_A));
+			assert_ok!(PalletBridge::unbond(Origin::signed(RELAYER_A), 40));
+			expect_event(Event::pallet_bridge(RawEvent::RelayerUnbonded(RELAYER_A, 40, 11)));
+			assert_eq!(PalletBridge::bond_of(RELAYER_A), 0);
+
+			// The unbonded amount stays bonded and can still be slashed
+			assert_noop!(PalletBridge::withdraw_unbonded(Origin::signed(RELAYER_B)), Error::<Test>::NotUnbonding);
+			assert_noop!(PalletBridge::withdraw_unbonded(Origin::signed(RELAYER_A)), Error::<Test>::StillUnbonding);
+			assert_ok!(PalletBridge::slash_relayer(Origin::root(), RELAYER_A, 10));
+			assert_eq!(PalletBridge::unbonding_of(RELAYER_A), Some((30, 11)));
+			assert_eq!(Balances::free_balance(PalletBridge::bond_account()), 30);
+
+			System::set_block_number(11);
+			let free = Balances::free_balance(RELAYER_A);
+			assert_ok!(PalletBridge::withdraw_unbonded(Origin::signed(RELAYER_A)));
+			assert_eq!(Balances::free_balance(RELAYER_A), free + 30);
+			assert_eq!(Balances::free_balance(PalletBridge::bond_account()), 0);
+			assert_eq!(Balances::reserved_balance(RELAYER_A), 500);
+			assert_eq!(PalletBridge::unbonding_of(RELAYER_A), None);
+		})
+	}
+
+	#[test]
+	fn relayer_votes_are_recorded_without_rewards() {
+		new_test_ext().execute_with(|| {
+			let prop_id = 1;
+			let src_id = 1;
+			let r_id = chainbridge::derive_resource_id(src_id, b"transfer");
+			let proposal = make_transfer_proposal(RELAYER_A, 10, r_id);
+			let pot = PalletBridge::relayer_pot();
+
+			assert_ok!(ChainBridge::set_threshold(Origin::root(), 1));
+			assert_ok!(ChainBridge::add_relayer(Origin::root(), RELAYER_A));
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), src_id));
+			assert_ok!(ChainBridge::set_resource(Origin::root(), r_id, b"PalletBridge.transfer".to_vec()));
+			assert_ok!(PalletBridge::set_relayer_incentives(Origin::root(), RelayerIncentives {
+				fee_share: Perbill::zero(),
+				reward_per_proposal: 1000,
+				min_bond: 100,
+			}));
+			assert_ok!(PalletBridge::bond(Origin::signed(RELAYER_A), 100));
+			assert_ok!(ChainBridge::acknowledge_proposal(Origin::signed(RELAYER_A), prop_id, src_id, r_id, Box::new(proposal.clone())));
+
+			// The pot is empty, but the vote counts
+			assert_ok!(PalletBridge::reward_relayers(Origin::signed(5), src_id, prop_id, Box::new(proposal.clone())));
+			expect_event(Event::pallet_bridge(RawEvent::RelayerVotesRecorded(src_id, prop_id)));
+			assert_eq!(PalletBridge::relayer_stats(RELAYER_A), RelayerStats { votes_for: 1, votes_against: 0, rewarded: 0, slashed: 0 });
+
+			// Once funded, the reward is paid without counting the vote twice
+			let _ = Balances::deposit_creating(&pot, 1000);
+			assert_ok!(PalletBridge::reward_relayers(Origin::signed(5), src_id, prop_id, Box::new(proposal.clone())));
+			expect_event(Event::pallet_bridge(RawEvent::RelayersRewarded(src_id, prop_id, 1000)));
+			assert_eq!(PalletBridge::relayer_stats(RELAYER_A), RelayerStats { votes_for: 1, votes_against: 0, rewarded: 1000, slashed: 0 });
 		})
 	}
 
@@ -876,8 +2199,77 @@ mod tests{
 					RELAYER_A,
 					10,
 				)),
+				Event::pallet_bridge(RawEvent::NativeTransferReceived(RELAYER_A, 10, r_id)),
 				Event::chainbridge(chainbridge::RawEvent::ProposalSucceeded(src_id, prop_id)),
 			]);
 		})
 	}
+
+	#[test]
+	fn discover_bridge_configuration() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(PalletBridge::whitelist_chain(Origin::signed(RELAYER_A), 3), BadOrigin);
+			assert_ok!(PalletBridge::whitelist_chain(Origin::root(), 3));
+			assert_ok!(PalletBridge::whitelist_chain(Origin::root(), 0));
+			assert!(PalletBridge::whitelist_chain(Origin::root(), 0).is_err());
+			assert_eq!(PalletBridge::chains(), vec![0, 3]);
+
+			// Relayers added through this module are listed, bonded or not
+			assert_ok!(ChainBridge::set_threshold(Origin::root(), TEST_THRESHOLD));
+			assert_noop!(PalletBridge::add_relayer(Origin::signed(RELAYER_A), RELAYER_A), BadOrigin);
+			assert_ok!(PalletBridge::add_relayer(Origin::root(), RELAYER_A));
+			assert_ok!(PalletBridge::add_relayer(Origin::root(), RELAYER_B));
+			assert!(ChainBridge::is_relayer(&RELAYER_B));
+			let mut relayers = PalletBridge::relayers();
+			relayers.relayers.sort();
+			assert_eq!(relayers, RelayerSet { relayers: vec![RELAYER_A, RELAYER_B], count: 2, threshold: TEST_THRESHOLD });
+			assert_ok!(PalletBridge::remove_relayer(Origin::root(), RELAYER_A));
+			assert!(!ChainBridge::is_relayer(&RELAYER_A));
+			assert_eq!(PalletBridge::relayers(), RelayerSet { relayers: vec![RELAYER_B], count: 1, threshold: TEST_THRESHOLD });
+
+			let transfer = b"PalletBridge.transfer".to_vec();
+			assert_ok!(ChainBridge::set_resource(Origin::root(), NativeTokenId::get(), transfer.clone()));
+			let dai = chainbridge::derive_resource_id(1, &blake2_128(b"DAI"));
+			assert_ok!(<bridge_mapping::Module<Test>>::set_asset_resource(dai, 7));
+			let resources = PalletBridge::resources();
+			assert_eq!(resources.len(), 3);
+			assert!(resources.contains(&ResourceInfo { resource_id: HashId::get(), ..Default::default() }));
+			assert!(resources.contains(&ResourceInfo { resource_id: NativeTokenId::get(), method: Some(transfer), ..Default::default() }));
+			assert!(resources.contains(&ResourceInfo { resource_id: dai, asset_id: Some(7), ..Default::default() }));
+
+			let fee = BridgeFee { flat: 10, bps: 250 };
+			assert_ok!(PalletBridge::set_bridge_fee(Origin::root(), 3, dai, Some(fee.clone())));
+			assert_eq!(PalletBridge::fee_schedule(), FeeSchedule {
+				native: Some(2000 * currency::CFG),
+				nft: Some(20 * currency::CFG),
+				fungible: Some(30 * currency::CFG),
+				bridge_fees: vec![(3, dai, fee)],
+			});
+			// Fungible transfers only pay the flat fee
+			assert_eq!(PalletBridge::quote_fungible(7, 3), Some(10));
+			assert_eq!(PalletBridge::quote_fungible(8, 3), Some(30 * currency::CFG));
+			assert_eq!(PalletBridge::quote_native(3, 1000), Some(2000 * currency::CFG));
+			assert_eq!(PalletBridge::quote_nft(RegistryId::zero(), 3), Some(20 * currency::CFG));
+		})
+	}
+
+	#[test]
+	fn runtime_upgrade_indexes_chains_and_relayers() {
+		new_test_ext().execute_with(|| {
+			// Chains and relayers added to the chainbridge before the indexes were kept
+			assert_ok!(ChainBridge::whitelist_chain(Origin::root(), 5));
+			assert_ok!(ChainBridge::add_relayer(Origin::root(), RELAYER_A));
+			assert_ok!(ChainBridge::add_relayer(Origin::root(), RELAYER_B));
+			assert_ok!(ChainBridge::remove_relayer(Origin::root(), RELAYER_B));
+			assert_eq!(PalletBridge::chains(), Vec::<chainbridge::ChainId>::new());
+			assert!(PalletBridge::relayers().relayers.is_empty());
+
+			<PalletBridge as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+			assert_eq!(PalletBridge::chains(), vec![5]);
+			assert_eq!(PalletBridge::relayers().relayers, vec![RELAYER_A]);
+
+			// The indexes are only built once
+			assert_eq!(<PalletBridge as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade(), 0);
+		})
+	}
 }
@@ -1,13 +1,61 @@
 /// Handling state rent fee payments for specific transactions
+use crate::constants::currency;
 use codec::{Decode, Encode};
 use frame_support::{
     decl_event, decl_module, decl_storage,
     dispatch::DispatchResult,
+    storage::migration,
     ensure,
-    traits::{Currency, EnsureOrigin, ExistenceRequirement, WithdrawReason},
-    weights::DispatchClass,
+    traits::{
+        Currency, EnsureOrigin, ExistenceRequirement, Get, Imbalance, OnUnbalanced,
+        WithdrawReason,
+    },
+    weights::{DispatchClass, Weight},
 };
-use frame_system::ensure_root;
+use frame_system::{ensure_root, ensure_signed};
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, CheckedDiv, Hash, SaturatedConversion, Saturating, Zero},
+    FixedPointNumber, FixedU128, PerThing, Perbill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Storage version after fees were keyed by [FeeKey]
+const FEE_KEY_VERSION: u64 = 1;
+
+/// Storage version after fee destinations were introduced
+const FEE_DESTINATION_VERSION: u64 = 2;
+
+/// Storage version after the price history of fees was introduced
+const FEE_HISTORY_VERSION: u64 = 3;
+
+/// Storage version after fungible asset transfers got their own fee
+const FEE_FUNGIBLE_BRIDGE_VERSION: u64 = 4;
+
+type NegativeImbalanceOf<T> = <pallet_balances::Module<T> as Currency<
+    <T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+
+/// Provides the current USD price of one CFG, to convert fees defined in USD.
+pub trait CfgPrice {
+    /// Returns the USD price of one CFG, or None if no price is available
+    fn usd_per_cfg() -> Option<FixedU128>;
+
+    /// Weight of looking up the price
+    fn weight() -> Weight;
+}
+
+impl CfgPrice for () {
+    fn usd_per_cfg() -> Option<FixedU128> {
+        None
+    }
+
+    fn weight() -> Weight {
+        0
+    }
+}
 
 /// The module's configuration trait.
 pub trait Trait: frame_system::Trait + pallet_balances::Trait + pallet_authorship::Trait {
@@ -15,8 +63,145 @@ pub trait Trait: frame_system::Trait + pallet_balances::Trait + pallet_authorshi
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     /// Required origin for changing fees
     type FeeChangeOrigin: EnsureOrigin<Self::Origin>;
+    /// Receives the block author share of fees
+    type Author: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// Account receiving the treasury share of fees
+    type TreasuryAccount: Get<Self::AccountId>;
+    /// Account receiving the staking reward share of fees
+    type StakingPotAccount: Get<Self::AccountId>;
+    /// Account holding the credits of sponsors
+    type CreditAccount: Get<Self::AccountId>;
+    /// Required origin for spending the treasury and staking reward shares of fees
+    type SpendOrigin: EnsureOrigin<Self::Origin>;
+    /// Maximum number of past prices kept per fee key
+    type MaxFeeHistory: Get<u32>;
+    /// Converts fees defined in USD to CFG
+    type CfgPrice: CfgPrice;
+}
+
+/// Protocol fees charged on top of the transaction fee, by the call they are charged for.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FeeKey {
+    /// Anchoring state rent per day, charged by `anchor::commit`
+    AnchorRentPerDay,
+    /// Minting an NFT from verified proofs, charged by `nfts::validate_mint`
+    NftValidate,
+    /// Moving an NFT to a target chain, charged by `bridge::transfer_asset`
+    NftBridge,
+    /// Moving native tokens to a target chain, charged by `bridge::transfer_native`
+    NativeBridge,
+    /// Moving fungible assets to a target chain, charged by `bridge::transfer_asset_fungible`
+    FungibleBridge,
+}
+
+/// Shares of a fee paid to each destination. Whatever is not assigned to a destination is burned.
+#[derive(Encode, Decode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FeeSplit {
+    pub author: Perbill,
+    pub treasury: Perbill,
+    pub staking: Perbill,
+}
+
+impl FeeSplit {
+    /// Pays the whole fee to the block author
+    pub fn to_author() -> Self {
+        FeeSplit {
+            author: Perbill::one(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the shares do not add up to more than the whole fee
+    pub fn is_valid(&self) -> bool {
+        [self.author, self.treasury, self.staking]
+            .iter()
+            .map(|share| share.deconstruct() as u64)
+            .sum::<u64>()
+            <= Perbill::ACCURACY as u64
+    }
+}
+
+/// Account collecting a share of the fees, see [FeeSplit]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FeePot {
+    Treasury,
+    StakingPot,
+}
+
+/// A protocol fee charged by a call
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+pub struct Charge<Balance> {
+    pub key: FeeKey,
+    #[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+    pub amount: Balance,
 }
 
+/// All protocol fees a call is charged on top of the transaction fee
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+pub struct Breakdown<Balance> {
+    pub charges: Vec<Charge<Balance>>,
+    #[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+    pub total: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Breakdown<Balance> {
+    pub fn new(charges: Vec<Charge<Balance>>) -> Self {
+        let total = charges
+            .iter()
+            .fold(Zero::zero(), |total: Balance, charge| total.saturating_add(charge.amount));
+        Breakdown { charges, total }
+    }
+}
+
+/// Balances do not fit into JSON numbers, so they are serialized as strings
+#[cfg(feature = "std")]
+pub(crate) fn serialize_as_string<S: serde::Serializer, T: std::fmt::Display>(
+    t: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&t.to_string())
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn serialize_option_as_string<S: serde::Serializer, T: std::fmt::Display>(
+    t: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match t {
+        Some(t) => serialize_as_string(t, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Fees of a beneficiary paid by a sponsor from its credits, once the beneficiary accepted
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Sponsorship<Balance> {
+    /// Fees that are sponsored. All fees are sponsored if empty.
+    pub keys: Vec<FeeKey>,
+    /// Maximum amount of fees the sponsor pays for the beneficiary in total
+    pub limit: Balance,
+    /// Amount of fees the sponsor paid for the beneficiary so far
+    pub spent: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> Sponsorship<Balance> {
+    /// Returns true if the fee for the key is sponsored and the amount is within the limit
+    fn covers(&self, key: FeeKey, amount: Balance) -> bool {
+        (self.keys.is_empty() || self.keys.contains(&key))
+            && self.spent.saturating_add(amount) <= self.limit
+    }
+}
+
+/// Fee as stored before fees were keyed by [FeeKey]. Only kept to migrate the legacy storage.
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "std", derive(Debug))]
 pub struct Fee<Hash, Balance> {
@@ -26,20 +211,76 @@ pub struct Fee<Hash, Balance> {
 
 decl_storage! {
     trait Store for Module<T: Trait> as Fees {
-        Fees get(fn fee) : map hasher(opaque_blake2_256) T::Hash => Fee<T::Hash, T::Balance>;
+        Fees get(fn fee) : map hasher(blake2_128_concat) FeeKey => Option<T::Balance>;
+
+        /// Where the fee for a key is paid to. Burned completely by default.
+        Destinations get(fn destination_of) : map hasher(blake2_128_concat) FeeKey => FeeSplit;
+
+        /// Fee changes to apply at the start of a block
+        ScheduledFees get(fn scheduled_fees) : map hasher(twox_64_concat) T::BlockNumber => Vec<(FeeKey, T::Balance)>;
+
+        /// Past CFG prices of a fee with the block they took effect, oldest first. Bounded by
+        /// [Trait::MaxFeeHistory]. The CFG price of a fee defined in USD changes with every feed
+        /// of the oracle, so it is recorded whenever the fee is charged at a different price.
+        FeeHistory get(fn fee_history) : map hasher(blake2_128_concat) FeeKey => Vec<(T::BlockNumber, T::Balance)>;
+
+        /// Fees defined in USD, with the same 18 decimals as CFG. Converted to CFG at the current
+        /// price when charged, falling back to the CFG price of the key without a current price.
+        UsdFees get(fn usd_fee) : map hasher(blake2_128_concat) FeeKey => Option<T::Balance>;
+
+        /// Credits a sponsor has deposited to pay fees of its beneficiaries, held by
+        /// [Trait::CreditAccount]
+        Credits get(fn credits_of) : map hasher(blake2_128_concat) T::AccountId => T::Balance;
+
+        /// Sponsorships of the fees of a beneficiary, by beneficiary and sponsor
+        Sponsorships get(fn sponsorship) : double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => Option<Sponsorship<T::Balance>>;
+
+        /// Sponsor whose sponsorship the beneficiary accepted, which pays its fees
+        SponsorOf get(fn sponsor_of) : map hasher(blake2_128_concat) T::AccountId => Option<T::AccountId>;
 
         Version: u64;
     }
     add_extra_genesis {
-        // Anchoring state rent fee per day
-        config(initial_fees): Vec<(T::Hash, T::Balance)>;
-        build(|config| Module::<T>::initialize_fees(&config.initial_fees))
+        config(initial_fees): Vec<(FeeKey, T::Balance)>;
+        config(destinations): Vec<(FeeKey, FeeSplit)>;
+        build(|config| {
+            Module::<T>::initialize_fees(&config.initial_fees);
+            config.destinations.iter().for_each(|(key, split)| Destinations::insert(key, split));
+            Version::put(FEE_FUNGIBLE_BRIDGE_VERSION);
+        })
     }
 }
 
 decl_event!(
-    pub enum Event<T> where <T as frame_system::Trait>::Hash, <T as pallet_balances::Trait>::Balance {
-        FeeChanged(Hash, Balance),
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+        <T as pallet_balances::Trait>::Balance,
+        <T as frame_system::Trait>::BlockNumber,
+    {
+        FeeChanged(FeeKey, Balance),
+        FeeDestinationChanged(FeeKey, FeeSplit),
+        /// The fee for the key will change to the price at the start of the block
+        FeeChangeScheduled(FeeKey, Balance, BlockNumber),
+        /// The fee changes for the key scheduled at the block have been cancelled
+        ScheduledFeeChangeCancelled(FeeKey, BlockNumber),
+        /// The fee for the key is defined in USD, or no longer if None
+        UsdFeeChanged(FeeKey, Option<Balance>),
+        /// The sponsor deposited the amount as credits
+        CreditsDeposited(AccountId, Balance),
+        /// The sponsor withdrew the amount of its credits
+        CreditsWithdrawn(AccountId, Balance),
+        /// The sponsor offers to pay fees of the beneficiary up to the limit
+        SponsorshipSet(AccountId, AccountId, Balance),
+        /// The sponsor no longer pays fees of the beneficiary
+        SponsorshipRevoked(AccountId, AccountId),
+        /// The beneficiary accepted the sponsorship of the sponsor
+        SponsorshipAccepted(AccountId, AccountId),
+        /// The beneficiary left the sponsorship of the sponsor
+        SponsorshipLeft(AccountId, AccountId),
+        /// The sponsor paid the fee for the key of the beneficiary
+        FeeSponsored(AccountId, AccountId, FeeKey, Balance),
+        /// The amount of the pot was paid to the account
+        PotSpent(FeePot, AccountId, Balance),
     }
 );
 
@@ -50,6 +291,53 @@ decl_module! {
         // this is needed only if you are using events in your module
         fn deposit_event() = default;
 
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let changes = <ScheduledFees<T>>::take(now);
+            let count = changes.len() as Weight;
+            for (key, price) in changes {
+                Self::change_fee(key, price);
+                Self::deposit_event(RawEvent::FeeChanged(key, price));
+            }
+
+            T::DbWeight::get().reads_writes(1 + count, 1 + 2 * count)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let version = Version::get();
+            if version >= FEE_FUNGIBLE_BRIDGE_VERSION {
+                return 0;
+            }
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            if version < FEE_KEY_VERSION {
+                weight = weight.saturating_add(Self::migrate_to_fee_keys());
+            }
+            if version < FEE_DESTINATION_VERSION {
+                // Anchoring rent used to be paid to the block author
+                Destinations::insert(FeeKey::AnchorRentPerDay, FeeSplit::to_author());
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            if version < FEE_HISTORY_VERSION {
+                // Start the history with the current prices
+                let now = <frame_system::Module<T>>::block_number();
+                let mut keys = 0;
+                for (key, price) in <Fees<T>>::iter() {
+                    <FeeHistory<T>>::insert(key, vec![(now, price)]);
+                    keys += 1;
+                }
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(keys, keys));
+            }
+            // Fungible asset transfers used to be charged the fee of native token transfers
+            if !<Fees<T>>::contains_key(FeeKey::FungibleBridge) {
+                let price = Self::fee(FeeKey::NativeBridge)
+                    .unwrap_or_else(|| (2000 * currency::CFG).saturated_into());
+                Self::change_fee(FeeKey::FungibleBridge, price);
+                weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 2));
+            }
+            Version::put(FEE_FUNGIBLE_BRIDGE_VERSION);
+
+            weight
+        }
+
         /// Set the given fee for the key
         ///
         /// # <weight>
@@ -57,28 +345,303 @@ decl_module! {
         /// - Contains a limited number of reads and writes.
         /// # </weight>
         #[weight = (195_000_000, DispatchClass::Operational)]
-        pub fn set_fee(origin, key: T::Hash, new_price: T::Balance) -> DispatchResult {
+        pub fn set_fee(origin, key: FeeKey, new_price: T::Balance) -> DispatchResult {
             Self::can_change_fee(origin)?;
             Self::change_fee(key, new_price);
 
             Self::deposit_event(RawEvent::FeeChanged(key, new_price));
             Ok(())
         }
+
+        /// Set how the fee for the key is split between the block author, the treasury and the
+        /// staking reward pot. The remainder is burned.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = (195_000_000, DispatchClass::Operational)]
+        pub fn set_fee_destination(origin, key: FeeKey, split: FeeSplit) -> DispatchResult {
+            Self::can_change_fee(origin)?;
+            ensure!(split.is_valid(), "fee split exceeds the whole fee");
+            Destinations::insert(key, split);
+
+            Self::deposit_event(RawEvent::FeeDestinationChanged(key, split));
+            Ok(())
+        }
+
+        /// Pay the amount collected in the pot to the account, i.e. to fund development out of
+        /// the treasury or to top up staking rewards.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = 195_000_000]
+        pub fn spend_from_pot(origin, pot: FeePot, to: T::AccountId, amount: T::Balance) -> DispatchResult {
+            T::SpendOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            <pallet_balances::Module<T> as Currency<_>>::transfer(
+                &Self::pot_account(pot),
+                &to,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            )?;
+
+            Self::deposit_event(RawEvent::PotSpent(pot, to, amount));
+            Ok(())
+        }
+
+        /// Schedule the fee for the key to change to the given price at the start of block `at`,
+        /// which gives integrators notice of the change.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = (195_000_000, DispatchClass::Operational)]
+        pub fn schedule_fee(origin, key: FeeKey, new_price: T::Balance, at: T::BlockNumber) -> DispatchResult {
+            Self::can_change_fee(origin)?;
+            ensure!(at > <frame_system::Module<T>>::block_number(), "fee change must be scheduled for a future block");
+            <ScheduledFees<T>>::append(at, (key, new_price));
+
+            Self::deposit_event(RawEvent::FeeChangeScheduled(key, new_price, at));
+            Ok(())
+        }
+
+        /// Cancel the changes of the fee for the key scheduled at block `at`
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = (195_000_000, DispatchClass::Operational)]
+        pub fn cancel_scheduled_fee(origin, key: FeeKey, at: T::BlockNumber) -> DispatchResult {
+            Self::can_change_fee(origin)?;
+            <ScheduledFees<T>>::try_mutate_exists(at, |changes| -> DispatchResult {
+                let mut remaining = changes.take().unwrap_or_default();
+                let count = remaining.len();
+                remaining.retain(|(k, _)| *k != key);
+                ensure!(remaining.len() < count, "no fee change scheduled for key");
+                if !remaining.is_empty() {
+                    *changes = Some(remaining);
+                }
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::ScheduledFeeChangeCancelled(key, at));
+            Ok(())
+        }
+
+        /// Define the fee for the key in USD, to be converted to CFG when charged. The CFG price
+        /// set for the key is used while no CFG/USD price is available. Passing None removes the
+        /// USD price.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = (195_000_000, DispatchClass::Operational)]
+        pub fn set_usd_fee(origin, key: FeeKey, usd_price: Option<T::Balance>) -> DispatchResult {
+            Self::can_change_fee(origin)?;
+            <UsdFees<T>>::set(key, usd_price);
+
+            Self::deposit_event(RawEvent::UsdFeeChanged(key, usd_price));
+            Ok(())
+        }
+
+        /// Deposit the amount from the caller as credits to pay the fees of its beneficiaries. The
+        /// credits are held by the credit account, apart from any reserve of the caller.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(3, 3) + 195_000_000]
+        pub fn deposit_credits(origin, amount: T::Balance) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            <pallet_balances::Module<T> as Currency<_>>::transfer(
+                &sponsor,
+                &T::CreditAccount::get(),
+                amount,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            <Credits<T>>::mutate(&sponsor, |credits| *credits = credits.saturating_add(amount));
+
+            Self::deposit_event(RawEvent::CreditsDeposited(sponsor, amount));
+            Ok(())
+        }
+
+        /// Withdraw the amount of the caller's credits back to its free balance
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(3, 3) + 195_000_000]
+        pub fn withdraw_credits(origin, amount: T::Balance) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            let credits = Self::credits_of(&sponsor);
+            ensure!(credits >= amount, "insufficient credits");
+            <pallet_balances::Module<T> as Currency<_>>::transfer(
+                &T::CreditAccount::get(),
+                &sponsor,
+                amount,
+                ExistenceRequirement::AllowDeath,
+            )?;
+            Self::set_credits(&sponsor, credits - amount);
+
+            Self::deposit_event(RawEvent::CreditsWithdrawn(sponsor, amount));
+            Ok(())
+        }
+
+        /// Offer to pay the fees of the beneficiary from the caller's credits, up to `limit` in
+        /// total. Only the fees for `keys` are paid, or all fees if `keys` is empty. The fees are
+        /// paid once the beneficiary accepted the sponsorship.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(1, 1) + 195_000_000]
+        pub fn sponsor(origin, beneficiary: T::AccountId, keys: Vec<FeeKey>, limit: T::Balance) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            let spent = Self::sponsorship(&beneficiary, &sponsor)
+                .map_or(Zero::zero(), |existing| existing.spent);
+            <Sponsorships<T>>::insert(&beneficiary, &sponsor, Sponsorship { keys, limit, spent });
+
+            Self::deposit_event(RawEvent::SponsorshipSet(sponsor, beneficiary, limit));
+            Ok(())
+        }
+
+        /// Stop paying the fees of the beneficiary
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2, 2) + 195_000_000]
+        pub fn revoke_sponsorship(origin, beneficiary: T::AccountId) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            <Sponsorships<T>>::take(&beneficiary, &sponsor).ok_or("beneficiary is not sponsored")?;
+            if Self::sponsor_of(&beneficiary).as_ref() == Some(&sponsor) {
+                <SponsorOf<T>>::remove(&beneficiary);
+            }
+
+            Self::deposit_event(RawEvent::SponsorshipRevoked(sponsor, beneficiary));
+            Ok(())
+        }
+
+        /// Let the sponsor pay the fees of the caller as it offered, instead of the sponsor
+        /// accepted before if any
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(1, 1) + 195_000_000]
+        pub fn accept_sponsorship(origin, sponsor: T::AccountId) -> DispatchResult {
+            let beneficiary = ensure_signed(origin)?;
+            ensure!(<Sponsorships<T>>::contains_key(&beneficiary, &sponsor), "sponsorship not offered");
+            <SponsorOf<T>>::insert(&beneficiary, &sponsor);
+
+            Self::deposit_event(RawEvent::SponsorshipAccepted(beneficiary, sponsor));
+            Ok(())
+        }
+
+        /// Pay the fees of the caller itself again instead of its sponsor
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// - Contains a limited number of reads and writes.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(1, 1) + 195_000_000]
+        pub fn leave_sponsorship(origin) -> DispatchResult {
+            let beneficiary = ensure_signed(origin)?;
+            let sponsor = <SponsorOf<T>>::take(&beneficiary).ok_or("no sponsorship accepted")?;
+
+            Self::deposit_event(RawEvent::SponsorshipLeft(beneficiary, sponsor));
+            Ok(())
+        }
     }
 }
 
 impl<T: Trait> Module<T> {
     /// Called by any other module who wants to trigger a fee payment for a given account.
     /// The current fee price can be retrieved via Fees::price_of()
-    pub fn pay_fee(from: T::AccountId, key: T::Hash) -> DispatchResult {
-        ensure!(<Fees<T>>::contains_key(key), "fee not found for key");
+    pub fn pay_fee(from: T::AccountId, key: FeeKey) -> DispatchResult {
+        let price = Self::price_of(key).ok_or("fee not found for key")?;
+        Self::charge(&from, key, price)
+    }
+
+    /// Withdraws the amount from the account and splits it between the destinations of the key.
+    /// Used for fees that are derived from the price of the key, i.e. rent for a number of days.
+    /// The fee is paid by the sponsor of the account instead, if it sponsors the fee and has
+    /// enough credits left.
+    pub fn charge(from: &T::AccountId, key: FeeKey, amount: T::Balance) -> DispatchResult {
+        Self::record_charged_price(key);
+        let fee = match Self::take_sponsored(from, key, amount) {
+            Some(fee) => fee,
+            None => <pallet_balances::Module<T> as Currency<_>>::withdraw(
+                from,
+                amount,
+                WithdrawReason::Fee.into(),
+                ExistenceRequirement::KeepAlive,
+            )?,
+        };
 
-        let single_fee = <Fees<T>>::get(key);
-        Self::pay_fee_to_author(from, single_fee.price)?;
+        let split = Self::destination_of(key);
+        let (author, rest) = fee.split(split.author * amount);
+        let (treasury, rest) = rest.split(split.treasury * amount);
+        let (staking, burned) = rest.split(split.staking * amount);
+        T::Author::on_unbalanced(author);
+        <pallet_balances::Module<T>>::resolve_creating(
+            &Self::pot_account(FeePot::Treasury),
+            treasury,
+        );
+        <pallet_balances::Module<T>>::resolve_creating(
+            &Self::pot_account(FeePot::StakingPot),
+            staking,
+        );
+        // Dropping the remainder reduces the total issuance
+        drop(burned);
 
         Ok(())
     }
 
+    /// Takes the fee from the credits of the accepted sponsor of the account, if the sponsorship
+    /// covers it
+    fn take_sponsored(beneficiary: &T::AccountId, key: FeeKey, amount: T::Balance) -> Option<NegativeImbalanceOf<T>> {
+        let sponsor = Self::sponsor_of(beneficiary)?;
+        let mut sponsorship = Self::sponsorship(beneficiary, &sponsor)?;
+        let credits = Self::credits_of(&sponsor);
+        if !sponsorship.covers(key, amount) || credits < amount {
+            return None;
+        }
+
+        let fee = <pallet_balances::Module<T> as Currency<_>>::withdraw(
+            &T::CreditAccount::get(),
+            amount,
+            WithdrawReason::Fee.into(),
+            ExistenceRequirement::AllowDeath,
+        ).ok()?;
+        Self::set_credits(&sponsor, credits - amount);
+        sponsorship.spent = sponsorship.spent.saturating_add(amount);
+        <Sponsorships<T>>::insert(beneficiary, &sponsor, sponsorship);
+
+        Self::deposit_event(RawEvent::FeeSponsored(sponsor, beneficiary.clone(), key, amount));
+        Some(fee)
+    }
+
+    fn set_credits(sponsor: &T::AccountId, credits: T::Balance) {
+        if credits.is_zero() {
+            <Credits<T>>::remove(sponsor);
+        } else {
+            <Credits<T>>::insert(sponsor, credits);
+        }
+    }
+
     /// Burns Fee from account
     pub fn burn_fee(from: &T::AccountId, fee: T::Balance) -> DispatchResult {
         let _ = <pallet_balances::Module<T> as Currency<_>>::withdraw(
@@ -105,17 +668,90 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    /// Weight of looking up the price of a fee with [price_of] and charging it with [charge],
+    /// to be added to the weight of calls charging fees
+    pub fn charge_weight() -> Weight {
+        // Price, history, sponsor, sponsorship, credits and destination of the fee, and the
+        // accounts it is split between
+        T::DbWeight::get()
+            .reads_writes(11, 7)
+            .saturating_add(T::CfgPrice::weight())
+    }
+
     /// Returns the current fee for the key
-    pub fn price_of(key: T::Hash) -> Option<T::Balance> {
-        //why this has been hashed again after passing to the function? sp_io::print(key.as_ref());
-        if <Fees<T>>::contains_key(&key) {
-            let single_fee = <Fees<T>>::get(&key);
-            Some(single_fee.price)
-        } else {
-            None
+    pub fn price_of(key: FeeKey) -> Option<T::Balance> {
+        Self::usd_fee(key)
+            .and_then(Self::usd_to_cfg)
+            .or_else(|| <Fees<T>>::get(key))
+    }
+
+    /// Returns the current price of every fee that is set
+    pub fn fees() -> Vec<(FeeKey, T::Balance)> {
+        let mut keys = <Fees<T>>::iter().map(|(key, _)| key).collect::<Vec<_>>();
+        for (key, _) in <UsdFees<T>>::iter() {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        keys.into_iter()
+            .filter_map(|key| Self::price_of(key).map(|price| (key, price)))
+            .collect()
+    }
+
+    /// Converts a USD amount to CFG at the current price
+    fn usd_to_cfg(usd: T::Balance) -> Option<T::Balance> {
+        let usd_per_cfg = T::CfgPrice::usd_per_cfg()?;
+        // Both amounts have 18 decimals, just like the fixed point number
+        FixedU128::from_inner(usd.saturated_into())
+            .checked_div(&usd_per_cfg)
+            .map(|cfg| cfg.into_inner().saturated_into())
+    }
+
+    /// Returns the CFG price of the fee for the key that was in effect at the given block, i.e.
+    /// the price it was charged at. Returns None if the block is older than the history kept for
+    /// the key.
+    pub fn price_at(key: FeeKey, block: T::BlockNumber) -> Option<T::Balance> {
+        Self::fee_history(key)
+            .into_iter()
+            .rev()
+            .find(|(changed_at, _)| *changed_at <= block)
+            .map(|(_, price)| price)
+    }
+
+    /// Change the fee for the given key
+    pub fn change_fee(key: FeeKey, fee: T::Balance) {
+        <Fees<T>>::insert(key, fee);
+        Self::record_price(key, fee);
+    }
+
+    /// Records the current price of the fee for the key in the history if it differs from the last
+    /// recorded price, i.e. if the fee is defined in USD and the CFG/USD price changed
+    fn record_charged_price(key: FeeKey) {
+        if let Some(price) = Self::price_of(key) {
+            if Self::fee_history(key).last().map(|(_, last)| *last) != Some(price) {
+                Self::record_price(key, price);
+            }
         }
     }
 
+    /// Adds the price the fee for the key has from now on to its history
+    fn record_price(key: FeeKey, fee: T::Balance) {
+        let now = <frame_system::Module<T>>::block_number();
+        <FeeHistory<T>>::mutate(key, |history| {
+            // Only the last change within a block is in effect
+            if history.last().map_or(false, |(changed_at, _)| *changed_at == now) {
+                history.pop();
+            }
+            history.push((now, fee));
+
+            let max = T::MaxFeeHistory::get() as usize;
+            if history.len() > max {
+                history.drain(..history.len() - max);
+            }
+        });
+    }
+
     /// Returns true if the given origin can change the fee
     fn can_change_fee(origin: T::Origin) -> DispatchResult {
         T::FeeChangeOrigin::try_origin(origin)
@@ -125,20 +761,67 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    /// Account collecting the shares of fees paid to the pot
+    pub fn pot_account(pot: FeePot) -> T::AccountId {
+        match pot {
+            FeePot::Treasury => T::TreasuryAccount::get(),
+            FeePot::StakingPot => T::StakingPotAccount::get(),
+        }
+    }
+
     /// Initialise fees for a fixed set of keys. i.e. For use in genesis
-    fn initialize_fees(fees: &[(T::Hash, T::Balance)]) {
+    fn initialize_fees(fees: &[(FeeKey, T::Balance)]) {
         fees.iter()
             .map(|(ref key, ref fee)| Self::change_fee(*key, *fee))
             .count();
     }
 
-    /// Change the fee for the given key
-    fn change_fee(key: T::Hash, fee: T::Balance) {
-        let new_fee = Fee {
-            key: key.clone(),
-            price: fee,
-        };
-        <Fees<T>>::insert(key, new_fee);
+    /// Moves the anchoring rent from its legacy hash key to [FeeKey::AnchorRentPerDay] and the
+    /// token transfer fee of the bridge to [FeeKey::NativeBridge], and sets the fees that used to
+    /// be constants in other modules. Returns the weight of the reads and writes performed.
+    fn migrate_to_fee_keys() -> Weight {
+        let legacy_key = <T as frame_system::Trait>::Hashing::hash_of(&0);
+        let legacy_rent = migration::take_storage_value::<Fee<T::Hash, T::Balance>>(
+            b"Fees",
+            b"Fees",
+            &sp_io::hashing::blake2_256(&legacy_key.encode()),
+        );
+        let mut reads = 1;
+        let mut writes = 0;
+        if let Some(fee) = legacy_rent {
+            Self::change_fee(FeeKey::AnchorRentPerDay, fee.price);
+            reads += 1;
+            writes += 2;
+        }
+
+        // The native token transfer fee used to be managed by the bridge
+        let token_transfer_fee = migration::take_storage_value::<T::Balance>(
+            b"PalletBridge",
+            b"TokenTransferFee",
+            &[],
+        );
+        reads += 1;
+        if let Some(fee) = token_transfer_fee {
+            Self::change_fee(FeeKey::NativeBridge, fee);
+            reads += 1;
+            writes += 2;
+        }
+
+        let defaults = [
+            (FeeKey::NftValidate, 10 * currency::CFG),
+            (FeeKey::NftBridge, 20 * currency::CFG),
+            (FeeKey::NativeBridge, 2000 * currency::CFG),
+        ];
+        for (key, price) in defaults.iter() {
+            reads += 1;
+            if !<Fees<T>>::contains_key(key) {
+                Self::change_fee(*key, (*price).saturated_into());
+                reads += 1;
+                writes += 2;
+            }
+        }
+
+        T::DbWeight::get().reads_writes(reads, writes)
     }
 }
 
@@ -149,15 +832,18 @@ mod tests {
 
     use frame_support::{
         assert_err, assert_noop, assert_ok, dispatch::DispatchError, impl_outer_origin,
-        ord_parameter_types, parameter_types, traits::FindAuthor, weights::Weight,
+        ord_parameter_types, parameter_types,
+        traits::{FindAuthor, OnInitialize, OnRuntimeUpgrade, ReservableCurrency},
+        weights::Weight,
         ConsensusEngineId,
     };
     use frame_system::EnsureSignedBy;
     use sp_core::H256;
     use sp_runtime::Perbill;
+    use std::cell::RefCell;
     use sp_runtime::{
         testing::Header,
-        traits::{BadOrigin, BlakeTwo256, Hash, IdentityLookup},
+        traits::{BadOrigin, BlakeTwo256, IdentityLookup},
     };
 
     impl_outer_origin! {
@@ -205,9 +891,56 @@ mod tests {
     ord_parameter_types! {
         pub const One: u64 = 1;
     }
+    parameter_types! {
+        pub const TreasuryAccount: u64 = 200;
+        pub const StakingAccount: u64 = 300;
+        pub const CreditAccount: u64 = 400;
+        pub const MaxFeeHistory: u32 = 3;
+    }
     impl Trait for Test {
         type Event = ();
         type FeeChangeOrigin = EnsureSignedBy<One, u64>;
+        type Author = ToAccount<BlockAuthor>;
+        type TreasuryAccount = TreasuryAccount;
+        type StakingPotAccount = StakingAccount;
+        type CreditAccount = CreditAccount;
+        type SpendOrigin = EnsureSignedBy<One, u64>;
+        type MaxFeeHistory = MaxFeeHistory;
+        type CfgPrice = MockCfgPrice;
+    }
+
+    thread_local! {
+        static USD_PER_CFG: RefCell<Option<FixedU128>> = RefCell::new(None);
+    }
+
+    pub struct MockCfgPrice;
+    impl MockCfgPrice {
+        fn set(price: Option<FixedU128>) {
+            USD_PER_CFG.with(|p| *p.borrow_mut() = price);
+        }
+    }
+    impl CfgPrice for MockCfgPrice {
+        fn usd_per_cfg() -> Option<FixedU128> {
+            USD_PER_CFG.with(|p| *p.borrow())
+        }
+
+        fn weight() -> Weight {
+            0
+        }
+    }
+
+    pub struct BlockAuthor;
+    impl Get<u64> for BlockAuthor {
+        fn get() -> u64 {
+            <pallet_authorship::Module<Test>>::author()
+        }
+    }
+
+    pub struct ToAccount<A>(sp_std::marker::PhantomData<A>);
+    impl<A: Get<u64>> OnUnbalanced<NegativeImbalanceOf<Test>> for ToAccount<A> {
+        fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<Test>) {
+            <pallet_balances::Module<Test>>::resolve_creating(&A::get(), amount);
+        }
     }
     parameter_types! {
         pub const ExistentialDeposit: u64 = 1;
@@ -270,8 +1003,8 @@ mod tests {
     #[test]
     fn multiple_new_fees_are_setable() {
         new_test_ext().execute_with(|| {
-            let fee_key1 = <Test as frame_system::Trait>::Hashing::hash_of(&11111);
-            let fee_key2 = <Test as frame_system::Trait>::Hashing::hash_of(&22222);
+            let fee_key1 = FeeKey::AnchorRentPerDay;
+            let fee_key2 = FeeKey::NftValidate;
 
             let price1: <Test as pallet_balances::Trait>::Balance = 666;
             let price2: <Test as pallet_balances::Trait>::Balance = 777;
@@ -283,24 +1016,24 @@ mod tests {
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key1, price1));
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key2, price2));
 
-            let loaded_fee1 = Fees::fee(fee_key1);
-            assert_eq!(loaded_fee1.price, price1);
+            let loaded_fee1 = Fees::fee(fee_key1).unwrap();
+            assert_eq!(loaded_fee1, price1);
 
-            let loaded_fee2 = Fees::fee(fee_key2);
-            assert_eq!(loaded_fee2.price, price2);
+            let loaded_fee2 = Fees::fee(fee_key2).unwrap();
+            assert_eq!(loaded_fee2, price2);
         });
     }
 
     #[test]
     fn fee_is_re_setable() {
         new_test_ext().execute_with(|| {
-            let fee_key = <Test as frame_system::Trait>::Hashing::hash_of(&11111);
+            let fee_key = FeeKey::AnchorRentPerDay;
 
             let initial_price: <Test as pallet_balances::Trait>::Balance = 666;
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, initial_price));
 
-            let loaded_fee = Fees::fee(fee_key);
-            assert_eq!(loaded_fee.price, initial_price);
+            let loaded_fee = Fees::fee(fee_key).unwrap();
+            assert_eq!(loaded_fee, initial_price);
 
             let new_price: <Test as pallet_balances::Trait>::Balance = 777;
             assert_noop!(
@@ -308,21 +1041,22 @@ mod tests {
                 BadOrigin
             );
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, new_price));
-            let again_loaded_fee = Fees::fee(fee_key);
-            assert_eq!(again_loaded_fee.price, new_price);
+            let again_loaded_fee = Fees::fee(fee_key).unwrap();
+            assert_eq!(again_loaded_fee, new_price);
         });
     }
 
     #[test]
     fn fee_payment_errors_if_not_set() {
         new_test_ext().execute_with(|| {
-            let fee_key = <Test as frame_system::Trait>::Hashing::hash_of(&111111);
+            let fee_key = FeeKey::NativeBridge;
             let fee_price: <Test as pallet_balances::Trait>::Balance = 90000;
             let author_old_balance = <pallet_balances::Module<Test>>::total_balance(&100);
 
             assert_err!(Fees::pay_fee(1, fee_key), "fee not found for key");
 
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, fee_price));
+            assert_ok!(Fees::set_fee_destination(Origin::signed(1), fee_key, FeeSplit::to_author()));
 
             // initial time paying will succeed as sufficient balance + fee is set
             assert_ok!(Fees::pay_fee(1, fee_key));
@@ -345,7 +1079,7 @@ mod tests {
     #[test]
     fn fee_payment_errors_if_insufficient_balance() {
         new_test_ext().execute_with(|| {
-            let fee_key = <Test as frame_system::Trait>::Hashing::hash_of(&111111);
+            let fee_key = FeeKey::NativeBridge;
             let fee_price: <Test as pallet_balances::Trait>::Balance = 90000;
 
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, fee_price));
@@ -365,7 +1099,7 @@ mod tests {
     #[test]
     fn fee_payment_subtracts_fees_from_account() {
         new_test_ext().execute_with(|| {
-            let fee_key = <Test as frame_system::Trait>::Hashing::hash_of(&111111);
+            let fee_key = FeeKey::NativeBridge;
             let fee_price: <Test as pallet_balances::Trait>::Balance = 90000;
             assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, fee_price));
 
@@ -388,7 +1122,7 @@ mod tests {
     #[test]
     fn fee_is_gettable() {
         new_test_ext().execute_with(|| {
-            let fee_key = <Test as frame_system::Trait>::Hashing::hash_of(&111111);
+            let fee_key = FeeKey::NativeBridge;
             let fee_price: <Test as pallet_balances::Trait>::Balance = 90000;
 
             //First run, the fee is not set yet and should return None
@@ -429,4 +1163,350 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn fees_are_listed() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(Fees::fees(), vec![]);
+
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NftBridge, 20));
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NativeBridge, 2000));
+
+            let mut fees = Fees::fees();
+            fees.sort_by_key(|(_, price)| *price);
+            assert_eq!(fees, vec![(FeeKey::NftBridge, 20), (FeeKey::NativeBridge, 2000)]);
+        });
+    }
+
+    #[test]
+    fn runtime_upgrade_migrates_legacy_fees() {
+        new_test_ext().execute_with(|| {
+            let legacy_key = <Test as frame_system::Trait>::Hashing::hash_of(&0);
+            migration::put_storage_value(
+                b"Fees",
+                b"Fees",
+                &sp_io::hashing::blake2_256(&legacy_key.encode()),
+                Fee { key: legacy_key, price: 42u64 },
+            );
+            migration::put_storage_value(b"PalletBridge", b"TokenTransferFee", &[], 7u64);
+
+            Fees::on_runtime_upgrade();
+            assert_eq!(Fees::price_of(FeeKey::AnchorRentPerDay), Some(42));
+            assert_eq!(Fees::price_of(FeeKey::NativeBridge), Some(7));
+            assert_eq!(migration::get_storage_value::<u64>(b"PalletBridge", b"TokenTransferFee", &[]), None);
+            assert!(Fees::price_of(FeeKey::NftValidate).is_some());
+            assert_eq!(Fees::destination_of(FeeKey::AnchorRentPerDay), FeeSplit::to_author());
+            assert_eq!(Fees::fee_history(FeeKey::AnchorRentPerDay), vec![(0, 42)]);
+            assert_eq!(Fees::price_of(FeeKey::FungibleBridge), Some(7));
+            assert_eq!(Version::get(), FEE_FUNGIBLE_BRIDGE_VERSION);
+
+            // Governance changes are kept on a second upgrade
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NftValidate, 1));
+            migration::put_storage_value(b"PalletBridge", b"TokenTransferFee", &[], 8u64);
+            assert_eq!(Fees::on_runtime_upgrade(), 0);
+            assert_eq!(Fees::price_of(FeeKey::NftValidate), Some(1));
+            assert_eq!(Fees::price_of(FeeKey::NativeBridge), Some(7));
+        });
+    }
+
+    #[test]
+    fn runtime_upgrade_adds_fungible_bridge_fee() {
+        new_test_ext().execute_with(|| {
+            Version::put(FEE_HISTORY_VERSION);
+            System::set_block_number(1);
+            Fees::change_fee(FeeKey::NativeBridge, 50);
+
+            System::set_block_number(2);
+            Fees::on_runtime_upgrade();
+            assert_eq!(Fees::price_of(FeeKey::FungibleBridge), Some(50));
+            assert_eq!(Fees::fee_history(FeeKey::NativeBridge), vec![(1, 50)]);
+            assert_eq!(Fees::fee_history(FeeKey::FungibleBridge), vec![(2, 50)]);
+            assert_eq!(Version::get(), FEE_FUNGIBLE_BRIDGE_VERSION);
+        });
+    }
+
+    #[test]
+    fn fee_is_split_between_destinations() {
+        new_test_ext().execute_with(|| {
+            let fee_key = FeeKey::NftBridge;
+            let total_issuance = <pallet_balances::Module<Test>>::total_issuance();
+            assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, 1000));
+
+            let invalid = FeeSplit {
+                author: Perbill::from_percent(50),
+                treasury: Perbill::from_percent(60),
+                staking: Perbill::zero(),
+            };
+            assert_noop!(
+                Fees::set_fee_destination(Origin::signed(1), fee_key, invalid),
+                "fee split exceeds the whole fee"
+            );
+
+            let split = FeeSplit {
+                author: Perbill::from_percent(10),
+                treasury: Perbill::from_percent(50),
+                staking: Perbill::from_percent(20),
+            };
+            assert_noop!(
+                Fees::set_fee_destination(Origin::signed(2), fee_key, split),
+                BadOrigin
+            );
+            assert_ok!(Fees::set_fee_destination(Origin::signed(1), fee_key, split));
+            assert_eq!(Fees::destination_of(fee_key), split);
+
+            assert_ok!(Fees::pay_fee(1, fee_key));
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(1), 100000 - 1000);
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(100), 100 + 100);
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(200), 500);
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(300), 200);
+            // The remaining 20% are burned
+            assert_eq!(<pallet_balances::Module<Test>>::total_issuance(), total_issuance - 200);
+        });
+    }
+
+    #[test]
+    fn pots_can_be_spent() {
+        new_test_ext().execute_with(|| {
+            let fee_key = FeeKey::NftBridge;
+            assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, 1000));
+            let split = FeeSplit {
+                treasury: Perbill::from_percent(50),
+                staking: Perbill::from_percent(50),
+                ..Default::default()
+            };
+            assert_ok!(Fees::set_fee_destination(Origin::signed(1), fee_key, split));
+            assert_ok!(Fees::pay_fee(1, fee_key));
+
+            assert_noop!(
+                Fees::spend_from_pot(Origin::signed(2), FeePot::Treasury, 2, 100),
+                BadOrigin
+            );
+            assert_ok!(Fees::spend_from_pot(Origin::signed(1), FeePot::Treasury, 2, 100));
+            assert_ok!(Fees::spend_from_pot(Origin::root(), FeePot::StakingPot, 2, 500));
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(2), 100000 + 600);
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(200), 400);
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(300), 0);
+            assert!(Fees::spend_from_pot(Origin::root(), FeePot::Treasury, 2, 401).is_err());
+        });
+    }
+
+    #[test]
+    fn breakdown_adds_up_charges() {
+        let breakdown = Breakdown::new(vec![
+            Charge { key: FeeKey::NftValidate, amount: 10u64 },
+            Charge { key: FeeKey::NftBridge, amount: 20u64 },
+        ]);
+        assert_eq!(breakdown.total, 30);
+        assert_eq!(Breakdown::<u64>::new(vec![]).total, 0);
+    }
+
+    #[test]
+    fn scheduled_fee_changes_are_applied() {
+        new_test_ext().execute_with(|| {
+            let fee_key = FeeKey::NftValidate;
+            assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, 10));
+
+            assert_noop!(
+                Fees::schedule_fee(Origin::signed(2), fee_key, 20, 5),
+                BadOrigin
+            );
+            assert_noop!(
+                Fees::schedule_fee(Origin::signed(1), fee_key, 20, 0),
+                "fee change must be scheduled for a future block"
+            );
+            assert_ok!(Fees::schedule_fee(Origin::signed(1), fee_key, 20, 5));
+            assert_ok!(Fees::schedule_fee(Origin::signed(1), FeeKey::NftBridge, 30, 5));
+            assert_ok!(Fees::cancel_scheduled_fee(Origin::signed(1), FeeKey::NftBridge, 5));
+            assert_noop!(
+                Fees::cancel_scheduled_fee(Origin::signed(1), FeeKey::NftBridge, 5),
+                "no fee change scheduled for key"
+            );
+
+            Fees::on_initialize(4);
+            assert_eq!(Fees::price_of(fee_key), Some(10));
+
+            System::set_block_number(5);
+            Fees::on_initialize(5);
+            assert_eq!(Fees::price_of(fee_key), Some(20));
+            assert_eq!(Fees::price_of(FeeKey::NftBridge), None);
+            assert!(Fees::scheduled_fees(5).is_empty());
+        });
+    }
+
+    #[test]
+    fn fee_history_is_bounded_and_queryable_by_block() {
+        new_test_ext().execute_with(|| {
+            let fee_key = FeeKey::AnchorRentPerDay;
+            for (block, price) in [(1, 10), (1, 11), (3, 30), (6, 60), (8, 80)].iter() {
+                System::set_block_number(*block);
+                assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, *price));
+            }
+
+            // Only the last change within a block is kept, and only the last 3 changes
+            assert_eq!(Fees::fee_history(fee_key), vec![(3, 30), (6, 60), (8, 80)]);
+
+            assert_eq!(Fees::price_at(fee_key, 2), None);
+            assert_eq!(Fees::price_at(fee_key, 3), Some(30));
+            assert_eq!(Fees::price_at(fee_key, 7), Some(60));
+            assert_eq!(Fees::price_at(fee_key, 100), Some(80));
+        });
+    }
+
+    #[test]
+    fn usd_fee_is_converted_at_current_price() {
+        new_test_ext().execute_with(|| {
+            let fee_key = FeeKey::AnchorRentPerDay;
+            assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, 1000));
+            assert_noop!(
+                Fees::set_usd_fee(Origin::signed(2), fee_key, Some(500)),
+                BadOrigin
+            );
+            assert_ok!(Fees::set_usd_fee(Origin::signed(1), fee_key, Some(500)));
+
+            // Falls back to the CFG price without a current CFG/USD price
+            MockCfgPrice::set(None);
+            assert_eq!(Fees::price_of(fee_key), Some(1000));
+
+            // 500 USD at 0.25 USD per CFG
+            MockCfgPrice::set(Some(FixedU128::saturating_from_rational(1, 4)));
+            assert_eq!(Fees::price_of(fee_key), Some(2000));
+            assert_eq!(Fees::fees(), vec![(fee_key, 2000)]);
+            assert_ok!(Fees::pay_fee(1, fee_key));
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(1), 100000 - 2000);
+
+            assert_ok!(Fees::set_usd_fee(Origin::signed(1), fee_key, None));
+            assert_eq!(Fees::price_of(fee_key), Some(1000));
+        });
+    }
+
+    #[test]
+    fn usd_fees_are_recorded_when_charged() {
+        new_test_ext().execute_with(|| {
+            let fee_key = FeeKey::AnchorRentPerDay;
+            System::set_block_number(1);
+            assert_ok!(Fees::set_fee(Origin::signed(1), fee_key, 1000));
+
+            System::set_block_number(5);
+            MockCfgPrice::set(Some(FixedU128::saturating_from_rational(1, 4)));
+            assert_ok!(Fees::set_usd_fee(Origin::signed(1), fee_key, Some(500)));
+            assert_ok!(Fees::pay_fee(1, fee_key));
+
+            // Charging again at the same price is not recorded
+            System::set_block_number(6);
+            assert_ok!(Fees::pay_fee(1, fee_key));
+
+            // 500 USD at 0.5 USD per CFG
+            System::set_block_number(8);
+            MockCfgPrice::set(Some(FixedU128::saturating_from_rational(1, 2)));
+            assert_ok!(Fees::pay_fee(1, fee_key));
+
+            assert_eq!(Fees::fee_history(fee_key), vec![(1, 1000), (5, 2000), (8, 1000)]);
+            assert_eq!(Fees::price_at(fee_key, 4), Some(1000));
+            assert_eq!(Fees::price_at(fee_key, 6), Some(2000));
+            assert_eq!(Fees::price_at(fee_key, 8), Some(1000));
+            assert_eq!(<pallet_balances::Module<Test>>::free_balance(1), 100000 - 5000);
+            MockCfgPrice::set(None);
+        });
+    }
+
+    #[test]
+    fn sponsor_pays_fees_from_credits() {
+        new_test_ext().execute_with(|| {
+            type Balances = pallet_balances::Module<Test>;
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NftValidate, 100));
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NftBridge, 100));
+
+            assert_ok!(Fees::deposit_credits(Origin::signed(1), 250));
+            assert_eq!(Balances::free_balance(1), 100000 - 250);
+            assert_eq!(Balances::free_balance(400), 250);
+            assert_eq!(Fees::credits_of(1), 250);
+
+            // 1 offers to sponsor validation fees of 2 up to 200, which 2 pays until it accepts
+            assert_ok!(Fees::sponsor(Origin::signed(1), 2, vec![FeeKey::NftValidate], 200));
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Balances::free_balance(2), 100000 - 100);
+            assert_noop!(Fees::accept_sponsorship(Origin::signed(2), 3), "sponsorship not offered");
+            assert_ok!(Fees::accept_sponsorship(Origin::signed(2), 1));
+
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Balances::free_balance(2), 100000 - 100);
+            assert_eq!(Balances::free_balance(400), 150);
+            assert_eq!(Fees::sponsorship(2, 1).unwrap().spent, 100);
+
+            // Not a sponsored fee
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftBridge));
+            assert_eq!(Balances::free_balance(2), 100000 - 200);
+
+            // The second validation reaches the limit, so the beneficiary pays the third
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Fees::sponsorship(2, 1).unwrap().spent, 200);
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Balances::free_balance(2), 100000 - 300);
+            assert_eq!(Fees::credits_of(1), 50);
+
+            assert_noop!(Fees::withdraw_credits(Origin::signed(1), 51), "insufficient credits");
+            assert_ok!(Fees::withdraw_credits(Origin::signed(1), 50));
+            assert_eq!(Balances::free_balance(400), 0);
+            assert_eq!(Balances::free_balance(1), 100000 - 200);
+
+            assert_noop!(Fees::revoke_sponsorship(Origin::signed(3), 2), "beneficiary is not sponsored");
+            assert_ok!(Fees::revoke_sponsorship(Origin::signed(1), 2));
+            assert_eq!(Fees::sponsorship(2, 1), None);
+            assert_eq!(Fees::sponsor_of(2), None);
+        });
+    }
+
+    #[test]
+    fn beneficiary_chooses_its_sponsor() {
+        new_test_ext().execute_with(|| {
+            type Balances = pallet_balances::Module<Test>;
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NftValidate, 100));
+            assert_ok!(Fees::deposit_credits(Origin::signed(1), 250));
+
+            // Another account offering a sponsorship does not keep 1 from sponsoring 2
+            assert_ok!(Fees::sponsor(Origin::signed(3), 2, vec![], 0));
+            assert_ok!(Fees::sponsor(Origin::signed(1), 2, vec![], 1000));
+            assert_ok!(Fees::accept_sponsorship(Origin::signed(2), 1));
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Balances::free_balance(2), 100000);
+
+            // Revoking another offer keeps the accepted sponsorship
+            assert_ok!(Fees::revoke_sponsorship(Origin::signed(3), 2));
+            assert_eq!(Fees::sponsor_of(2), Some(1));
+
+            // The beneficiary can leave the sponsorship
+            assert_ok!(Fees::leave_sponsorship(Origin::signed(2)));
+            assert_noop!(Fees::leave_sponsorship(Origin::signed(2)), "no sponsorship accepted");
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Balances::free_balance(2), 100000 - 100);
+            assert_eq!(Fees::credits_of(1), 150);
+        });
+    }
+
+    #[test]
+    fn credits_are_held_apart_from_reserves() {
+        new_test_ext().execute_with(|| {
+            type Balances = pallet_balances::Module<Test>;
+            assert_ok!(Fees::set_fee(Origin::signed(1), FeeKey::NftValidate, 100));
+            assert_ok!(Fees::deposit_credits(Origin::signed(1), 250));
+            assert_ok!(Fees::sponsor(Origin::signed(1), 2, vec![], 1000));
+            assert_ok!(Fees::accept_sponsorship(Origin::signed(2), 1));
+
+            // Slashing a reserve of the sponsor held for another reason leaves the credits
+            assert_ok!(Balances::reserve(&1, 200));
+            let _ = Balances::slash_reserved(&1, 200);
+            assert_eq!(Balances::reserved_balance(1), 0);
+
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_eq!(Balances::free_balance(2), 100000);
+            assert_eq!(Fees::credits_of(1), 150);
+
+            // Sponsored fees and withdrawals leave reserves held for other reasons
+            assert_ok!(Balances::reserve(&1, 300));
+            assert_ok!(Fees::pay_fee(2, FeeKey::NftValidate));
+            assert_ok!(Fees::withdraw_credits(Origin::signed(1), 50));
+            assert_eq!(Balances::reserved_balance(1), 300);
+            assert_eq!(Fees::credits_of(1), 0);
+        });
+    }
 }
@@ -0,0 +1,315 @@
+//! # NFT Fractionalization
+//!
+//! Splits ownership of an NFT of the [nft] module into a fixed number of fungible shares.
+//! Fractionalizing locks the NFT into this module's account and credits all shares to the
+//! former owner. Shares can be transferred freely between accounts. An account that holds
+//! all shares of an asset can redeem them to become the single owner of the NFT again.
+
+use crate::nft;
+use crate::va_registry::types::{AssetId, RegistryId, TokenId};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::Get,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{traits::AccountIdConversion, ModuleId};
+
+/// Account that holds the locked NFTs while they are fractionalized.
+const MODULE_ID: ModuleId = ModuleId(*b"cf/fract");
+
+/// Number of shares of a fractionalized asset.
+pub type Shares = u128;
+
+pub trait Trait: frame_system::Trait + nft::Trait {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Fractions {
+        /// Total number of shares issued for a fractionalized asset.
+        TotalShares get(fn total_shares): map hasher(blake2_128_concat) AssetId => Option<Shares>;
+        /// Number of shares of a fractionalized asset held by an account.
+        SharesOf get(fn shares_of): double_map hasher(blake2_128_concat) AssetId, hasher(blake2_128_concat) T::AccountId => Shares;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+    {
+        /// The asset has been locked and the given number of shares issued to the account.
+        Fractionalized(AssetId, AccountId, Shares),
+        /// Shares of the asset have been transferred from the first account to the second.
+        SharesTransferred(AssetId, AccountId, AccountId, Shares),
+        /// All shares of the asset have been redeemed and the asset unlocked to the account.
+        Redeemed(AssetId, AccountId),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// The asset has already been fractionalized.
+        AlreadyFractionalized,
+        /// The asset has not been fractionalized.
+        NotFractionalized,
+        /// At least one share must be issued.
+        ZeroShares,
+        /// The account does not hold enough shares for the operation.
+        InsufficientShares,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+        fn deposit_event() = default;
+
+        /// Lock an asset owned by the caller and issue `shares` shares of it to the caller.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2,3) + 195_000_000]
+        pub fn fractionalize(origin,
+                             registry_id: RegistryId,
+                             token_id: TokenId,
+                             shares: Shares,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(shares > 0, Error::<T>::ZeroShares);
+
+            let asset_id = AssetId(registry_id, token_id);
+            ensure!(!TotalShares::contains_key(&asset_id), Error::<T>::AlreadyFractionalized);
+
+            // Fails if the caller does not own the asset
+            <nft::Module<T>>::lock(&who, &Self::account_id(), &asset_id)?;
+
+            TotalShares::insert(&asset_id, shares);
+            SharesOf::<T>::insert(&asset_id, &who, shares);
+
+            Self::deposit_event(RawEvent::Fractionalized(asset_id, who, shares));
+
+            Ok(())
+        }
+
+        /// Transfer `amount` shares of a fractionalized asset to `dest_account`.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(3,2) + 195_000_000]
+        pub fn transfer_shares(origin,
+                               dest_account: T::AccountId,
+                               registry_id: RegistryId,
+                               token_id: TokenId,
+                               amount: Shares,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let asset_id = AssetId(registry_id, token_id);
+            ensure!(TotalShares::contains_key(&asset_id), Error::<T>::NotFractionalized);
+
+            let from_shares = Self::shares_of(&asset_id, &who)
+                .checked_sub(amount)
+                .ok_or(Error::<T>::InsufficientShares)?;
+
+            if from_shares == 0 {
+                SharesOf::<T>::remove(&asset_id, &who);
+            } else {
+                SharesOf::<T>::insert(&asset_id, &who, from_shares);
+            }
+            // Total shares are fixed at issuance, so this can not overflow
+            SharesOf::<T>::mutate(&asset_id, &dest_account, |shares| *shares += amount);
+
+            Self::deposit_event(RawEvent::SharesTransferred(asset_id, who, dest_account, amount));
+
+            Ok(())
+        }
+
+        /// Redeem all shares of a fractionalized asset. The caller must hold every share,
+        /// and receives the unlocked asset in return.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(3,3) + 195_000_000]
+        pub fn redeem(origin,
+                      registry_id: RegistryId,
+                      token_id: TokenId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let asset_id = AssetId(registry_id, token_id);
+            let total = Self::total_shares(&asset_id)
+                .ok_or(Error::<T>::NotFractionalized)?;
+            ensure!(Self::shares_of(&asset_id, &who) == total, Error::<T>::InsufficientShares);
+
+            <nft::Module<T>>::unlock(&Self::account_id(), &who, &asset_id)?;
+
+            SharesOf::<T>::remove(&asset_id, &who);
+            TotalShares::remove(&asset_id);
+
+            Self::deposit_event(RawEvent::Redeemed(asset_id, who));
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// The account holding all currently fractionalized assets.
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{assert_err, assert_ok, impl_outer_origin, parameter_types, weights::Weight};
+    use sp_core::{H160, H256, U256};
+    use sp_runtime::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+        Perbill,
+    };
+    use unique_assets::traits::{Mintable, Unique};
+
+    impl_outer_origin! {
+        pub enum Origin for Test where system = frame_system {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl frame_system::Trait for Test {
+        type BaseCallFilter = ();
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type DbWeight = ();
+        type BlockExecutionWeight = ();
+        type ExtrinsicBaseWeight = ();
+        type MaximumExtrinsicWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type ModuleToIndex = ();
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+    }
+
+    parameter_types! {
+        pub const MaxMetadataLength: u32 = 1024;
+    }
+    impl nft::Trait for Test {
+        type Event = ();
+        type AssetInfo = crate::va_registry::types::AssetInfo;
+        type MaxMetadataLength = MaxMetadataLength;
+    }
+
+    impl Trait for Test {
+        type Event = ();
+    }
+
+    type Fractions = Module<Test>;
+    type Nft = nft::Module<Test>;
+
+    const OWNER: u64 = 1;
+    const INVESTOR: u64 = 2;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap()
+            .into()
+    }
+
+    // Mints an asset to the owner and returns its id
+    fn mint_asset(owner: u64) -> AssetId {
+        let asset_id = AssetId(H160::zero(), U256::one());
+        assert_ok!(<Nft as Mintable>::mint(&owner, &owner, &asset_id, Default::default()));
+        asset_id
+    }
+
+    #[test]
+    fn fractionalize_locks_asset() {
+        new_test_ext().execute_with(|| {
+            let AssetId(registry_id, token_id) = mint_asset(OWNER);
+            let asset_id = AssetId(registry_id, token_id);
+
+            // Only the owner can fractionalize
+            assert_err!(Fractions::fractionalize(Origin::signed(INVESTOR), registry_id, token_id, 100),
+                        nft::Error::<Test>::NotAssetOwner);
+            assert_err!(Fractions::fractionalize(Origin::signed(OWNER), registry_id, token_id, 0),
+                        Error::<Test>::ZeroShares);
+
+            assert_ok!(Fractions::fractionalize(Origin::signed(OWNER), registry_id, token_id, 100));
+            assert_eq!(<Nft as Unique>::owner_of(&asset_id), Some(Fractions::account_id()));
+            assert_eq!(Fractions::total_shares(&asset_id), Some(100));
+            assert_eq!(Fractions::shares_of(&asset_id, OWNER), 100);
+
+            // Can not fractionalize twice
+            assert_err!(Fractions::fractionalize(Origin::signed(OWNER), registry_id, token_id, 100),
+                        Error::<Test>::AlreadyFractionalized);
+        });
+    }
+
+    #[test]
+    fn transfer_shares() {
+        new_test_ext().execute_with(|| {
+            let AssetId(registry_id, token_id) = mint_asset(OWNER);
+            let asset_id = AssetId(registry_id, token_id);
+
+            assert_err!(Fractions::transfer_shares(Origin::signed(OWNER), INVESTOR, registry_id, token_id, 10),
+                        Error::<Test>::NotFractionalized);
+            assert_ok!(Fractions::fractionalize(Origin::signed(OWNER), registry_id, token_id, 100));
+
+            assert_ok!(Fractions::transfer_shares(Origin::signed(OWNER), INVESTOR, registry_id, token_id, 40));
+            assert_eq!(Fractions::shares_of(&asset_id, OWNER), 60);
+            assert_eq!(Fractions::shares_of(&asset_id, INVESTOR), 40);
+
+            assert_err!(Fractions::transfer_shares(Origin::signed(INVESTOR), OWNER, registry_id, token_id, 41),
+                        Error::<Test>::InsufficientShares);
+        });
+    }
+
+    #[test]
+    fn redeem_requires_all_shares() {
+        new_test_ext().execute_with(|| {
+            let AssetId(registry_id, token_id) = mint_asset(OWNER);
+            let asset_id = AssetId(registry_id, token_id);
+
+            assert_ok!(Fractions::fractionalize(Origin::signed(OWNER), registry_id, token_id, 100));
+            assert_ok!(Fractions::transfer_shares(Origin::signed(OWNER), INVESTOR, registry_id, token_id, 1));
+
+            // Owner is missing one share
+            assert_err!(Fractions::redeem(Origin::signed(OWNER), registry_id, token_id),
+                        Error::<Test>::InsufficientShares);
+
+            // Investor buys out the owner and redeems
+            assert_ok!(Fractions::transfer_shares(Origin::signed(OWNER), INVESTOR, registry_id, token_id, 99));
+            assert_ok!(Fractions::redeem(Origin::signed(INVESTOR), registry_id, token_id));
+
+            assert_eq!(<Nft as Unique>::owner_of(&asset_id), Some(INVESTOR));
+            assert_eq!(Fractions::total_shares(&asset_id), None);
+            assert_eq!(Fractions::shares_of(&asset_id, INVESTOR), 0);
+        });
+    }
+}
@@ -1,8 +1,9 @@
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use node_primitives::Balance;
-use sp_runtime::traits::Convert;
-use frame_support::traits::{OnUnbalanced, Currency};
+use node_primitives::{AccountId, Balance};
+use sp_runtime::{ModuleId, traits::{AccountIdConversion, Convert}};
+use sp_std::marker::PhantomData;
+use frame_support::traits::{OnUnbalanced, Currency, Get};
 use crate::{Balances, Authorship, NegativeImbalance};
 use frame_support::weights::{WeightToFeeCoefficient, WeightToFeePolynomial, WeightToFeeCoefficients};
 use smallvec::smallvec;
@@ -15,6 +16,14 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 	}
 }
 
+/// Account of the module id, i.e. for the treasury.
+pub struct ModuleAccount<Id>(PhantomData<Id>);
+impl<Id: Get<ModuleId>> Get<AccountId> for ModuleAccount<Id> {
+	fn get() -> AccountId {
+		Id::get().into_account()
+	}
+}
+
 /// Struct that handles the conversion of Balance -> `u64`. This is used for staking's election
 /// calculation.
 pub struct CurrencyToVoteHandler;
@@ -11,7 +11,7 @@ use frame_support::{
         Weight,
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
     },
-    traits::{Currency, KeyOwnerProofSystem, Randomness, LockIdentifier, InstanceFilter},
+    traits::{Currency, Filter, KeyOwnerProofSystem, Randomness, LockIdentifier, InstanceFilter},
 };
 use codec::{Encode, Decode};
 use sp_core::{
@@ -23,7 +23,7 @@ use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
 use sp_api::{decl_runtime_apis, impl_runtime_apis};
 use sp_runtime::{
 	Perbill, Perquintill, ApplyExtrinsicResult,
-	impl_opaque_keys, generic, create_runtime_str, FixedPointNumber,
+	impl_opaque_keys, generic, create_runtime_str, FixedPointNumber, ModuleId,
 };
 use sp_runtime::curve::PiecewiseLinear;
 use sp_runtime::transaction_validity::{TransactionValidity, TransactionSource, TransactionPriority};
@@ -42,7 +42,7 @@ use pallet_im_online::sr25519::{AuthorityId as ImOnlineId};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
 pub use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
-use frame_system::{EnsureSigned, EnsureRoot};
+use frame_system::EnsureRoot;
 use pallet_session::{historical as pallet_session_historical};
 use sp_inherents::{InherentData, CheckInherentsResult};
 use crate::anchor::AnchorData;
@@ -57,8 +57,10 @@ pub use pallet_staking::StakerStatus;
 
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
-use impls::{CurrencyToVoteHandler, Author};
+use impls::{CurrencyToVoteHandler, Author, ModuleAccount};
 use bridge as pallet_bridge;
+pub use bridge::{BridgeFee, FeeSchedule, RelayerSet, ResourceInfo};
+pub use rad_claims::{ProgramFunding, ProgramId, RootHashInfo};
 
 // Bridge access control list pallet
 use bridge_mapping;
@@ -67,7 +69,7 @@ use bridge_mapping;
 pub mod anchor;
 
 /// Fees for TXs
-mod fees;
+pub mod fees;
 
 /// common utilities
 mod common;
@@ -90,6 +92,18 @@ mod va_registry;
 /// nft module
 mod nft;
 
+/// nft fractionalization module
+mod fractions;
+
+/// nft collateralized loans module
+mod loans;
+
+/// CFG/USD price oracle module
+mod oracle;
+
+/// fungible multi asset module
+mod assets;
+
 /// Constant values used within the runtime.
 pub mod constants;
 use constants::{time::*, currency::*};
@@ -117,10 +131,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // and set impl_version to 0. If only runtime
     // implementation changes and behavior does not, then leave spec_version as
     // is and increment impl_version.
-    spec_version: 242,
+    spec_version: 243,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
 };
 
 /// Native version.
@@ -148,8 +162,21 @@ parameter_types! {
 
 const_assert!(AvailableBlockRatio::get().deconstruct() >= AVERAGE_ON_INITIALIZE_WEIGHT.deconstruct());
 
+/// Rejects the calls of the chainbridge that manage relayers and chains, which are managed through
+/// the bridge module instead so that it knows them.
+pub struct BaseFilter;
+impl Filter<Call> for BaseFilter {
+	fn filter(call: &Call) -> bool {
+		!matches!(call,
+			Call::ChainBridge(chainbridge::Call::add_relayer(..)) |
+			Call::ChainBridge(chainbridge::Call::remove_relayer(..)) |
+			Call::ChainBridge(chainbridge::Call::whitelist_chain(..))
+		)
+	}
+}
+
 impl frame_system::Trait for Runtime {
-    type BaseCallFilter = ();
+    type BaseCallFilter = BaseFilter;
     /// The ubiquitous origin type.
     type Origin = Origin;
 	/// The aggregated dispatch type that is available for extrinsics.
@@ -764,15 +791,35 @@ impl pallet_identity::Trait for Runtime {
 
 impl anchor::Trait for Runtime {}
 
+parameter_types! {
+	/// Account receiving the treasury share of protocol fees
+	pub const TreasuryModuleId: ModuleId = ModuleId(*b"cf/trsry");
+	/// Account collecting the staking reward share of protocol fees
+	pub const StakingPotModuleId: ModuleId = ModuleId(*b"cf/stpot");
+	/// Account holding the credits sponsors deposited to pay fees of their beneficiaries
+	pub const FeeCreditsModuleId: ModuleId = ModuleId(*b"cf/fcred");
+	/// Number of past prices kept per fee, so that charged fees can be audited
+	pub const MaxFeeHistory: u32 = 100;
+}
+
 /// Fees module implementation
 impl fees::Trait for Runtime {
 	type Event = Event;
 	/// A straight majority of the council can change the fees.
 	type FeeChangeOrigin = pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	type Author = Author;
+	type TreasuryAccount = ModuleAccount<TreasuryModuleId>;
+	type StakingPotAccount = ModuleAccount<StakingPotModuleId>;
+	type CreditAccount = ModuleAccount<FeeCreditsModuleId>;
+	/// Two thirds of the council can spend the treasury and staking reward shares of fees.
+	type SpendOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	type MaxFeeHistory = MaxFeeHistory;
+	type CfgPrice = Oracle;
 }
 
 impl nfts::Trait for Runtime {
     type Event = Event;
+    type WeightInfo = nfts::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -798,15 +845,19 @@ parameter_types! {
     pub HashId: chainbridge::ResourceId = chainbridge::derive_resource_id(1, &blake2_128(b"cent_nft_hash"));
 	//TODO rename xRAD to xCFG and create new mapping
 	pub NativeTokenId: chainbridge::ResourceId = chainbridge::derive_resource_id(1, &blake2_128(b"xRAD"));
+	/// Former relayers can be slashed for a week after unbonding
+	pub const RelayerBondingDuration: BlockNumber = 7 * DAYS;
 }
 
 impl bridge::Trait for Runtime {
 	type Event = Event;
-	type BridgeOrigin = EnsureSigned<AccountId>;
+	/// Only proposals executed by the chainbridge can transfer to this chain.
+	type BridgeOrigin = chainbridge::EnsureBridge<Runtime>;
 	type Currency = Balances;
 	type HashId = HashId;
 	type NativeTokenId = NativeTokenId;
     type AdminOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+    type BondingDuration = RelayerBondingDuration;
 }
 
 
@@ -835,6 +886,10 @@ impl rad_claims::Trait for Runtime {
     type UnsignedPriority = UnsignedPriority;
     type AdminOrigin = pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
     type Currency = Balances;
+    type Signature = Signature;
+    type Signer = <Signature as traits::Verify>::Signer;
+    type Vesting = Vesting;
+    type MinVestedTransfer = MinVestedTransfer;
 }
 
 parameter_types! {
@@ -853,17 +908,57 @@ impl va_registry::Trait for Runtime {
     type Event = Event;
 }
 
+parameter_types! {
+    /// Bounds the metadata stored with an nft, i.e. received from another chain
+    pub const MaxMetadataLength: u32 = 1024;
+}
+
 impl nft::Trait for Runtime {
     type Event = Event;
     type AssetInfo = va_registry::types::AssetInfo;
+    type MaxMetadataLength = MaxMetadataLength;
 }
 
 impl bridge_mapping::Trait for Runtime {
+    type Event = Event;
     type ResourceId = bridge::ResourceId;
     type Address = bridge::Address;
+    type AssetId = assets::AssetId;
     type AdminOrigin = pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
 }
 
+impl fractions::Trait for Runtime {
+    type Event = Event;
+}
+
+impl assets::Trait for Runtime {
+    type Event = Event;
+    type Balance = Balance;
+    type AdminOrigin = pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+}
+
+impl loans::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type AdminOrigin = pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+}
+
+parameter_types! {
+	/// Feeds older than an hour are not part of the oracle price
+	pub const MaxPriceAge: BlockNumber = HOURS;
+	/// An oracle price needs fresh feeds of at least three feeders
+	pub const MinFeeders: u32 = 3;
+	pub const MaxFeeders: u32 = 20;
+}
+
+impl oracle::Trait for Runtime {
+	type Event = Event;
+	type AdminOrigin = pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	type MaxPriceAge = MaxPriceAge;
+	type MinFeeders = MinFeeders;
+	type MaxFeeders = MaxFeeders;
+}
+
 // Frame Order in this block dictates the index of each one in the metadata
 // Any addition should be done at the bottom
 // Any deletion affects the following frames during runtime upgrades
@@ -907,7 +1002,11 @@ construct_runtime!(
         Vesting: pallet_vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		Registry: va_registry::{Module, Call, Storage, Event<T>},
 		Nft: nft::{Module, Call, Storage, Event<T>},
-        BridgeMapping: bridge_mapping::{Module, Call, Storage},
+        BridgeMapping: bridge_mapping::{Module, Call, Storage, Event<T>},
+        Fractions: fractions::{Module, Call, Storage, Event<T>},
+        Loans: loans::{Module, Call, Storage, Event<T>},
+        Oracle: oracle::{Module, Call, Storage, Event<T>},
+        Assets: assets::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -946,6 +1045,89 @@ decl_runtime_apis! {
     pub trait AnchorApi {
         fn get_anchor_by_id(id: Hash) -> Option<AnchorData<Hash, BlockNumber>>;
     }
+
+    /// The API to query protocol fees.
+    pub trait FeesApi {
+        /// Returns the current price of every protocol fee that is set.
+        fn fees() -> Vec<(fees::FeeKey, Balance)>;
+        /// Returns the price of the fee that was in effect at the given block, if still in the history.
+        fn price_at(key: fees::FeeKey, block: BlockNumber) -> Option<Balance>;
+    }
+
+    /// The API to quote the protocol fees of a call.
+    pub trait ProtocolFeeApi {
+        /// Returns every protocol fee the call is charged on top of the transaction fee.
+        fn quote(call: Call) -> fees::Breakdown<Balance>;
+    }
+
+    /// The API to query the accountability of bridge relayers.
+    pub trait BridgeRelayerApi {
+        /// Returns the bond of the relayer.
+        fn relayer_bond(relayer: AccountId) -> Balance;
+        /// Returns the participation of the relayer in executed proposals.
+        fn relayer_stats(relayer: AccountId) -> pallet_bridge::RelayerStats<Balance>;
+    }
+
+    /// The API to discover the configuration of the bridge.
+    pub trait BridgeApi {
+        /// Returns the ids of all whitelisted chains.
+        fn chains() -> Vec<u8>;
+        /// Returns the relayers and the number of votes required to execute a proposal.
+        fn relayers() -> RelayerSet<AccountId>;
+        /// Returns every resource of the bridge and what it is mapped to.
+        fn resources() -> Vec<ResourceInfo>;
+        /// Returns the default fees of transfers and every bridge fee.
+        fn fees() -> FeeSchedule<Balance>;
+        /// Returns the fee of a `transfer_native` call.
+        fn quote_native(dest_id: u8, amount: Balance) -> Option<Balance>;
+        /// Returns the fee of a `transfer_asset` call.
+        fn quote_nft(registry_id: sp_core::H160, dest_id: u8) -> Option<Balance>;
+        /// Returns the fee of a `transfer_asset_fungible` call.
+        fn quote_fungible(asset_id: u32, dest_id: u8) -> Option<Balance>;
+    }
+
+    /// The API to query the root hashes of reward claim programs.
+    pub trait RadClaimsApi {
+        /// Returns the root hashes of the program that rewards can still be claimed against,
+        /// together with the block they were uploaded at.
+        fn active_root_hashes(program_id: ProgramId) -> Vec<(Hash, RootHashInfo<Balance, BlockNumber>)>;
+        /// Returns the balance of the funding account of the program against its outstanding
+        /// liability.
+        fn funding(program_id: ProgramId) -> ProgramFunding<Balance>;
+    }
+}
+
+/// Protocol fees charged by a call on top of the transaction fee. The fees of the calls wrapped
+/// by batches, proxies and multisigs are added up.
+fn protocol_fees_of(call: &Call) -> Vec<fees::Charge<Balance>> {
+	let charge = |key: fees::FeeKey, amount: Option<Balance>| -> Vec<fees::Charge<Balance>> {
+		amount.map(|amount| fees::Charge { key, amount }).into_iter().collect()
+	};
+	let fee = |key: fees::FeeKey| charge(key, Fees::price_of(key));
+
+	match call {
+		Call::Anchor(anchor::Call::commit(_, _, _, stored_until_date)) =>
+			charge(fees::FeeKey::AnchorRentPerDay, Anchor::rent_until(*stored_until_date)),
+		Call::Nfts(nfts::Call::validate_mint(..)) => fee(fees::FeeKey::NftValidate),
+		Call::PalletBridge(pallet_bridge::Call::transfer_asset(_, registry_id, _, dest_id)) =>
+			charge(fees::FeeKey::NftBridge, PalletBridge::quote_nft(*registry_id, *dest_id)),
+		Call::PalletBridge(pallet_bridge::Call::transfer_asset_fungible(_, asset_id, _, dest_id)) =>
+			charge(fees::FeeKey::FungibleBridge, PalletBridge::quote_fungible(*asset_id, *dest_id)),
+		Call::PalletBridge(pallet_bridge::Call::transfer_native(amount, _, dest_id)) =>
+			charge(fees::FeeKey::NativeBridge, PalletBridge::quote_native(*dest_id, *amount)),
+		Call::Utility(pallet_utility::Call::batch(calls)) |
+		Call::Utility(pallet_utility::Call::batch_all(calls)) =>
+			calls.iter().flat_map(protocol_fees_of).collect(),
+		Call::Utility(pallet_utility::Call::as_derivative(_, call)) |
+		Call::Proxy(pallet_proxy::Call::proxy(_, _, call)) |
+		Call::Multisig(pallet_multisig::Call::as_multi_threshold_1(_, call)) |
+		Call::MultiAccount(substrate_pallet_multi_account::Call::as_multi(.., call)) =>
+			protocol_fees_of(call),
+		// The call is only passed encoded, and may be left out until the final approval
+		Call::Multisig(pallet_multisig::Call::as_multi(_, _, _, call, ..)) =>
+			Call::decode(&mut &call[..]).map(|call| protocol_fees_of(&call)).unwrap_or_default(),
+		_ => Vec::new(),
+	}
 }
 
 impl_runtime_apis! {
@@ -1122,6 +1304,72 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl self::FeesApi<Block> for Runtime {
+		fn fees() -> Vec<(fees::FeeKey, Balance)> {
+			Fees::fees()
+		}
+
+		fn price_at(key: fees::FeeKey, block: BlockNumber) -> Option<Balance> {
+			Fees::price_at(key, block)
+		}
+	}
+
+	impl self::ProtocolFeeApi<Block> for Runtime {
+		fn quote(call: Call) -> fees::Breakdown<Balance> {
+			fees::Breakdown::new(protocol_fees_of(&call))
+		}
+	}
+
+	impl self::BridgeRelayerApi<Block> for Runtime {
+		fn relayer_bond(relayer: AccountId) -> Balance {
+			PalletBridge::bond_of(relayer)
+		}
+
+		fn relayer_stats(relayer: AccountId) -> pallet_bridge::RelayerStats<Balance> {
+			PalletBridge::relayer_stats(relayer)
+		}
+	}
+
+	impl self::BridgeApi<Block> for Runtime {
+		fn chains() -> Vec<u8> {
+			PalletBridge::chains()
+		}
+
+		fn relayers() -> RelayerSet<AccountId> {
+			PalletBridge::relayers()
+		}
+
+		fn resources() -> Vec<ResourceInfo> {
+			PalletBridge::resources()
+		}
+
+		fn fees() -> FeeSchedule<Balance> {
+			PalletBridge::fee_schedule()
+		}
+
+		fn quote_native(dest_id: u8, amount: Balance) -> Option<Balance> {
+			PalletBridge::quote_native(dest_id, amount)
+		}
+
+		fn quote_nft(registry_id: sp_core::H160, dest_id: u8) -> Option<Balance> {
+			PalletBridge::quote_nft(registry_id, dest_id)
+		}
+
+		fn quote_fungible(asset_id: u32, dest_id: u8) -> Option<Balance> {
+			PalletBridge::quote_fungible(asset_id, dest_id)
+		}
+	}
+
+	impl self::RadClaimsApi<Block> for Runtime {
+		fn active_root_hashes(program_id: ProgramId) -> Vec<(Hash, RootHashInfo<Balance, BlockNumber>)> {
+			RadClaims::active_root_hashes(program_id)
+		}
+
+		fn funding(program_id: ProgramId) -> ProgramFunding<Balance> {
+			RadClaims::funding(program_id)
+		}
+	}
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn dispatch_benchmark(
@@ -1142,6 +1390,7 @@ impl_runtime_apis! {
             let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat, &whitelist);
 
             add_benchmark!(params, batches, va_registry, Registry);
+            add_benchmark!(params, batches, nfts, Nfts);
 
             if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 		    Ok(batches)
@@ -1152,7 +1401,9 @@ impl_runtime_apis! {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use frame_support::{assert_noop, traits::EnsureOrigin};
     use frame_system::offchain::CreateSignedTransaction;
+    use sp_runtime::traits::BadOrigin;
 
     #[test]
     fn validate_transaction_submitter_bounds() {
@@ -1162,4 +1413,85 @@ mod tests {
 
         is_submit_signed_transaction::<Runtime>();
     }
+
+    #[test]
+    fn protocol_fees_of_calls() {
+        let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+        fees::GenesisConfig::<Runtime> {
+            initial_fees: vec![
+                (fees::FeeKey::NftBridge, 20 * CFG),
+                (fees::FeeKey::NativeBridge, 2000 * CFG),
+                (fees::FeeKey::FungibleBridge, 30 * CFG),
+            ],
+            destinations: vec![],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
+        sp_io::TestExternalities::new(t).execute_with(|| {
+            let native = Call::PalletBridge(pallet_bridge::Call::transfer_native(1, vec![], 0));
+            let asset = Call::PalletBridge(pallet_bridge::Call::transfer_asset(
+                vec![], Default::default(), Default::default(), 0));
+            let fungible = Call::PalletBridge(pallet_bridge::Call::transfer_asset_fungible(vec![], 0, 1, 0));
+            let remark = Call::System(frame_system::Call::remark(vec![]));
+
+            assert_eq!(protocol_fees_of(&native),
+                       vec![fees::Charge { key: fees::FeeKey::NativeBridge, amount: 2000 * CFG }]);
+            assert_eq!(protocol_fees_of(&fungible),
+                       vec![fees::Charge { key: fees::FeeKey::FungibleBridge, amount: 30 * CFG }]);
+            assert_eq!(protocol_fees_of(&remark), vec![]);
+
+            let batch = Call::Utility(pallet_utility::Call::batch(vec![native.clone(), asset, remark]));
+            assert_eq!(fees::Breakdown::new(protocol_fees_of(&batch)).total, 2020 * CFG);
+
+            let batch_all = Call::Utility(pallet_utility::Call::batch_all(vec![batch, native.clone()]));
+            assert_eq!(fees::Breakdown::new(protocol_fees_of(&batch_all)).total, 4020 * CFG);
+
+            let proxy = Call::Proxy(pallet_proxy::Call::proxy(Default::default(), None, Box::new(batch_all)));
+            let derivative = Call::Utility(pallet_utility::Call::as_derivative(0, Box::new(proxy)));
+            assert_eq!(fees::Breakdown::new(protocol_fees_of(&derivative)).total, 4020 * CFG);
+
+            let multisig = Call::Multisig(pallet_multisig::Call::as_multi(
+                2, vec![], None, native.encode(), false, 0));
+            assert_eq!(protocol_fees_of(&multisig),
+                       vec![fees::Charge { key: fees::FeeKey::NativeBridge, amount: 2000 * CFG }]);
+        });
+    }
+
+    #[test]
+    fn bridge_calls_require_bridge_origin() {
+        let t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+        sp_io::TestExternalities::new(t).execute_with(|| {
+            let bridge = chainbridge::Module::<Runtime>::account_id();
+            let other = AccountId::from([1; 32]);
+
+            assert_noop!(
+                PalletBridge::transfer(Origin::signed(other.clone()), other.clone(), CFG, NativeTokenId::get()),
+                BadOrigin
+            );
+            assert_noop!(
+                PalletBridge::receive_nonfungible(Origin::signed(other.clone()), other.clone(), Default::default(), vec![], HashId::get()),
+                BadOrigin
+            );
+            assert_noop!(
+                Nfts::receive_document(Origin::signed(other.clone()), other.clone(), [0; 20],
+                                       Default::default(), Default::default(), Default::default()),
+                BadOrigin
+            );
+            assert_eq!(
+                <Runtime as pallet_bridge::Trait>::BridgeOrigin::try_origin(Origin::signed(bridge.clone())).ok(),
+                Some(bridge)
+            );
+        });
+    }
+
+    #[test]
+    fn chainbridge_relayers_and_chains_are_managed_by_the_bridge() {
+        let relayer = AccountId::from([1; 32]);
+        assert!(!BaseFilter::filter(&Call::ChainBridge(chainbridge::Call::add_relayer(relayer.clone()))));
+        assert!(!BaseFilter::filter(&Call::ChainBridge(chainbridge::Call::whitelist_chain(2))));
+        assert!(BaseFilter::filter(&Call::PalletBridge(pallet_bridge::Call::add_relayer(relayer))));
+        assert!(BaseFilter::filter(&Call::PalletBridge(pallet_bridge::Call::whitelist_chain(2))));
+    }
 }
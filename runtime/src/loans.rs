@@ -0,0 +1,526 @@
+//! # NFT Collateralized Loans
+//!
+//! Lets the owner of an NFT borrow against it. The borrower requests a loan, which locks the
+//! NFT into this module's account as collateral. A lender funds the request by paying out the
+//! principal to the borrower. From then on, interest accrues on the outstanding debt every
+//! block until the debt is repaid. Once the debt is repaid in full, the borrower closes the
+//! loan and gets the NFT back. If the debt is still outstanding at maturity, the lender or the
+//! admin origin can seize the NFT on behalf of the lender.
+
+use crate::nft;
+use crate::va_registry::types::{AssetId, RegistryId, TokenId};
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::{Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, Get},
+};
+use frame_system::ensure_signed;
+use sp_runtime::{
+    traits::{AccountIdConversion, BadOrigin, SaturatedConversion, Saturating, Zero},
+    FixedPointNumber, FixedU128, ModuleId,
+};
+
+/// Account that holds the collateral of all open loans.
+const MODULE_ID: ModuleId = ModuleId(*b"cf/loans");
+
+/// Loans are identified using a nonce in storage.
+pub type LoanId = u64;
+
+type BalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// Lifecycle of a loan.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoanStatus {
+    /// Collateral is locked, waiting for a lender to fund the loan.
+    Requested,
+    /// Principal has been paid out and interest is accruing.
+    Active,
+    /// Debt has been repaid and the collateral returned to the borrower.
+    Closed,
+    /// Debt was outstanding at maturity and the collateral went to the lender.
+    Seized,
+}
+
+/// All data for an instance of a loan.
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct Loan<AccountId, Balance, BlockNumber> {
+    pub borrower: AccountId,
+    /// Set once the loan is funded.
+    pub lender: Option<AccountId>,
+    /// The NFT locked as collateral.
+    pub collateral: AssetId,
+    pub principal: Balance,
+    /// Interest accrued per block on the outstanding debt, compounded every block.
+    pub rate_per_block: FixedU128,
+    /// Block by which the debt must be repaid.
+    pub maturity: BlockNumber,
+    /// Outstanding debt including interest up to `last_accrued`.
+    pub debt: Balance,
+    /// Block up to which interest has been added to `debt`.
+    pub last_accrued: BlockNumber,
+    pub status: LoanStatus,
+}
+
+pub trait Trait: frame_system::Trait + nft::Trait {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Currency that principal and repayments are paid in.
+    type Currency: Currency<Self::AccountId>;
+    /// Origin that can seize the collateral of a defaulted loan on behalf of its lender.
+    type AdminOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Loans {
+        /// Nonce for generating new loan ids.
+        NextLoanId: LoanId;
+        /// All loans by id.
+        Loans get(fn loan): map hasher(blake2_128_concat) LoanId => Option<Loan<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        /// A loan has been requested by the borrower against the asset.
+        LoanRequested(LoanId, AccountId, AssetId),
+        /// A loan request has been cancelled and its collateral returned.
+        LoanCancelled(LoanId),
+        /// A loan has been funded by the lender with the principal.
+        LoanFunded(LoanId, AccountId, Balance),
+        /// The given amount has been repaid, leaving the remaining debt.
+        LoanRepaid(LoanId, Balance, Balance),
+        /// A repaid loan has been closed and its collateral returned to the borrower.
+        LoanClosed(LoanId),
+        /// The collateral of a defaulted loan has been transferred to the lender.
+        LoanSeized(LoanId, AccountId),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// No loan exists for the given id.
+        LoanDoesNotExist,
+        /// The loan is not in the status required for the operation.
+        InvalidLoanStatus,
+        /// The caller is not the borrower of the loan.
+        NotBorrower,
+        /// Principal must be greater than zero.
+        ZeroPrincipal,
+        /// Maturity must be in the future.
+        MaturityInPast,
+        /// The loan has matured and can no longer be funded.
+        LoanMatured,
+        /// The loan still has outstanding debt.
+        DebtOutstanding,
+        /// The loan has not matured yet.
+        LoanNotMatured,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+        fn deposit_event() = default;
+
+        /// Request a loan against an asset owned by the caller. The asset is locked as
+        /// collateral until the loan is cancelled, closed or seized.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2,3) + 195_000_000]
+        pub fn request(origin,
+                       registry_id: RegistryId,
+                       token_id: TokenId,
+                       principal: BalanceOf<T>,
+                       rate_per_block: FixedU128,
+                       maturity: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!principal.is_zero(), Error::<T>::ZeroPrincipal);
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(maturity > now, Error::<T>::MaturityInPast);
+
+            // Fails if the caller does not own the asset
+            let asset_id = AssetId(registry_id, token_id);
+            <nft::Module<T>>::lock(&who, &Self::account_id(), &asset_id)?;
+
+            let loan_id = NextLoanId::get();
+            NextLoanId::put(loan_id.saturating_add(1));
+            Loans::<T>::insert(loan_id, Loan {
+                borrower: who.clone(),
+                lender: None,
+                collateral: asset_id.clone(),
+                principal,
+                rate_per_block,
+                maturity,
+                debt: Zero::zero(),
+                last_accrued: now,
+                status: LoanStatus::Requested,
+            });
+
+            Self::deposit_event(RawEvent::LoanRequested(loan_id, who, asset_id));
+
+            Ok(())
+        }
+
+        /// Cancel a loan request that has not been funded yet and unlock its collateral.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2,2) + 195_000_000]
+        pub fn cancel(origin, loan_id: LoanId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let loan = Self::loan(loan_id).ok_or(Error::<T>::LoanDoesNotExist)?;
+            ensure!(loan.borrower == who, Error::<T>::NotBorrower);
+            ensure!(loan.status == LoanStatus::Requested, Error::<T>::InvalidLoanStatus);
+
+            <nft::Module<T>>::unlock(&Self::account_id(), &who, &loan.collateral)?;
+            Loans::<T>::remove(loan_id);
+
+            Self::deposit_event(RawEvent::LoanCancelled(loan_id));
+
+            Ok(())
+        }
+
+        /// Fund a requested loan. The principal is transferred from the caller, who becomes
+        /// the lender, to the borrower.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(3,3) + 195_000_000]
+        pub fn fund(origin, loan_id: LoanId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut loan = Self::loan(loan_id).ok_or(Error::<T>::LoanDoesNotExist)?;
+            ensure!(loan.status == LoanStatus::Requested, Error::<T>::InvalidLoanStatus);
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(loan.maturity > now, Error::<T>::LoanMatured);
+
+            T::Currency::transfer(&who, &loan.borrower, loan.principal, KeepAlive)?;
+
+            loan.lender = Some(who.clone());
+            loan.debt = loan.principal;
+            loan.last_accrued = now;
+            loan.status = LoanStatus::Active;
+            let principal = loan.principal;
+            Loans::<T>::insert(loan_id, loan);
+
+            Self::deposit_event(RawEvent::LoanFunded(loan_id, who, principal));
+
+            Ok(())
+        }
+
+        /// Repay up to `amount` of the outstanding debt of an active loan to its lender.
+        /// Anyone can repay on behalf of the borrower.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(3,3) + 195_000_000]
+        pub fn repay(origin, loan_id: LoanId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut loan = Self::loan(loan_id).ok_or(Error::<T>::LoanDoesNotExist)?;
+            ensure!(loan.status == LoanStatus::Active, Error::<T>::InvalidLoanStatus);
+
+            Self::accrue(&mut loan, <frame_system::Module<T>>::block_number());
+            let paid = amount.min(loan.debt);
+            let lender = loan.lender.clone().ok_or(Error::<T>::InvalidLoanStatus)?;
+            T::Currency::transfer(&who, &lender, paid, KeepAlive)?;
+
+            loan.debt = loan.debt.saturating_sub(paid);
+            let remaining = loan.debt;
+            Loans::<T>::insert(loan_id, loan);
+
+            Self::deposit_event(RawEvent::LoanRepaid(loan_id, paid, remaining));
+
+            Ok(())
+        }
+
+        /// Close a fully repaid loan and return its collateral to the borrower.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2,2) + 195_000_000]
+        pub fn close(origin, loan_id: LoanId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut loan = Self::loan(loan_id).ok_or(Error::<T>::LoanDoesNotExist)?;
+            ensure!(loan.borrower == who, Error::<T>::NotBorrower);
+            ensure!(loan.status == LoanStatus::Active, Error::<T>::InvalidLoanStatus);
+            ensure!(loan.debt.is_zero(), Error::<T>::DebtOutstanding);
+
+            <nft::Module<T>>::unlock(&Self::account_id(), &who, &loan.collateral)?;
+            loan.status = LoanStatus::Closed;
+            Loans::<T>::insert(loan_id, loan);
+
+            Self::deposit_event(RawEvent::LoanClosed(loan_id));
+
+            Ok(())
+        }
+
+        /// Transfer the collateral of a loan that has matured with outstanding debt to its
+        /// lender. Must be called by the lender or the admin origin.
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2,2) + 195_000_000]
+        pub fn seize(origin, loan_id: LoanId) -> DispatchResult {
+            let mut loan = Self::loan(loan_id).ok_or(Error::<T>::LoanDoesNotExist)?;
+            ensure!(loan.status == LoanStatus::Active, Error::<T>::InvalidLoanStatus);
+            let lender = loan.lender.clone().ok_or(Error::<T>::InvalidLoanStatus)?;
+            Self::ensure_lender_or_admin(origin, &lender)?;
+
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(now >= loan.maturity, Error::<T>::LoanNotMatured);
+            Self::accrue(&mut loan, now);
+            ensure!(!loan.debt.is_zero(), Error::<T>::InvalidLoanStatus);
+
+            <nft::Module<T>>::unlock(&Self::account_id(), &lender, &loan.collateral)?;
+            loan.status = LoanStatus::Seized;
+            Loans::<T>::insert(loan_id, loan);
+
+            Self::deposit_event(RawEvent::LoanSeized(loan_id, lender));
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// The account holding the collateral of all open loans.
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    /// Returns the outstanding debt of a loan including interest up to the current block.
+    pub fn debt_of(loan_id: LoanId) -> Option<BalanceOf<T>> {
+        Self::loan(loan_id).map(|mut loan| {
+            Self::accrue(&mut loan, <frame_system::Module<T>>::block_number());
+            loan.debt
+        })
+    }
+
+    /// Adds the interest compounded per block since the last accrual to the debt.
+    fn accrue(loan: &mut Loan<T::AccountId, BalanceOf<T>, T::BlockNumber>, now: T::BlockNumber) {
+        if now <= loan.last_accrued {
+            return;
+        }
+        let blocks: u32 = now.saturating_sub(loan.last_accrued).saturated_into();
+        let factor = FixedU128::one()
+            .saturating_add(loan.rate_per_block)
+            .saturating_pow(blocks as usize);
+        let debt: u128 = loan.debt.saturated_into();
+
+        loan.debt = factor.saturating_mul_int(debt).saturated_into();
+        loan.last_accrued = now;
+    }
+
+    /// Ensure that the given origin is either the lender of a loan or the [AdminOrigin].
+    fn ensure_lender_or_admin(origin: T::Origin, lender: &T::AccountId) -> DispatchResult {
+        T::AdminOrigin::try_origin(origin)
+            .map(|_| ())
+            .or_else(|o| match ensure_signed(o) {
+                Ok(who) if &who == lender => Ok(()),
+                _ => Err(BadOrigin),
+            })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{
+        assert_err, assert_noop, assert_ok, impl_outer_origin, ord_parameter_types,
+        parameter_types, weights::Weight,
+    };
+    use frame_system::EnsureSignedBy;
+    use sp_core::{H160, H256, U256};
+    use sp_runtime::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+        Perbill,
+    };
+    use unique_assets::traits::{Mintable, Unique};
+
+    impl_outer_origin! {
+        pub enum Origin for Test where system = frame_system {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl frame_system::Trait for Test {
+        type BaseCallFilter = ();
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type DbWeight = ();
+        type BlockExecutionWeight = ();
+        type ExtrinsicBaseWeight = ();
+        type MaximumExtrinsicWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type ModuleToIndex = ();
+        type AccountData = pallet_balances::AccountData<u128>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+    }
+
+    parameter_types! {
+        pub const ExistentialDeposit: u128 = 1;
+    }
+    impl pallet_balances::Trait for Test {
+        type Balance = u128;
+        type DustRemoval = ();
+        type Event = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type AccountStore = System;
+        type WeightInfo = ();
+    }
+
+    parameter_types! {
+        pub const MaxMetadataLength: u32 = 1024;
+    }
+    impl nft::Trait for Test {
+        type Event = ();
+        type AssetInfo = crate::va_registry::types::AssetInfo;
+        type MaxMetadataLength = MaxMetadataLength;
+    }
+
+    ord_parameter_types! {
+        pub const Admin: u64 = 9;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type AdminOrigin = EnsureSignedBy<Admin, u64>;
+    }
+
+    type Loans = Module<Test>;
+    type Nft = nft::Module<Test>;
+    type System = frame_system::Module<Test>;
+    type Balances = pallet_balances::Module<Test>;
+
+    const BORROWER: u64 = 1;
+    const LENDER: u64 = 2;
+    const OTHER: u64 = 3;
+    const ENDOWED_BALANCE: u128 = 1_000_000;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Test> {
+            balances: vec![(BORROWER, ENDOWED_BALANCE), (LENDER, ENDOWED_BALANCE), (OTHER, ENDOWED_BALANCE)],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    // Mints an asset to the borrower and requests a loan of 1000 against it with
+    // 1% interest per block, maturing at block 11.
+    fn request_loan() -> AssetId {
+        let asset_id = AssetId(H160::zero(), U256::one());
+        assert_ok!(<Nft as Mintable>::mint(&BORROWER, &BORROWER, &asset_id, Default::default()));
+        assert_ok!(Loans::request(Origin::signed(BORROWER),
+                                  asset_id.0,
+                                  asset_id.1,
+                                  1000,
+                                  FixedU128::saturating_from_rational(1, 100),
+                                  11));
+        asset_id
+    }
+
+    #[test]
+    fn request_locks_collateral() {
+        new_test_ext().execute_with(|| {
+            let asset_id = request_loan();
+            assert_eq!(<Nft as Unique>::owner_of(&asset_id), Some(Loans::account_id()));
+            assert_eq!(Loans::loan(0).unwrap().status, LoanStatus::Requested);
+
+            // Collateral can not be pledged twice
+            assert_err!(Loans::request(Origin::signed(BORROWER), asset_id.0, asset_id.1, 1000,
+                                       FixedU128::zero(), 11),
+                        nft::Error::<Test>::NotAssetOwner);
+
+            // Only the borrower can cancel
+            assert_noop!(Loans::cancel(Origin::signed(OTHER), 0), Error::<Test>::NotBorrower);
+            assert_ok!(Loans::cancel(Origin::signed(BORROWER), 0));
+            assert_eq!(<Nft as Unique>::owner_of(&asset_id), Some(BORROWER));
+            assert_eq!(Loans::loan(0), None);
+        });
+    }
+
+    #[test]
+    fn fund_repay_and_close() {
+        new_test_ext().execute_with(|| {
+            let asset_id = request_loan();
+            assert_ok!(Loans::fund(Origin::signed(LENDER), 0));
+            assert_eq!(Balances::free_balance(BORROWER), ENDOWED_BALANCE + 1000);
+            assert_eq!(Balances::free_balance(LENDER), ENDOWED_BALANCE - 1000);
+
+            // Two blocks of 1% interest compounded
+            System::set_block_number(3);
+            assert_eq!(Loans::debt_of(0), Some(1020));
+
+            // Can not close with outstanding debt
+            assert_noop!(Loans::close(Origin::signed(BORROWER), 0), Error::<Test>::DebtOutstanding);
+
+            // Repaying more than the debt only pays the debt
+            assert_ok!(Loans::repay(Origin::signed(BORROWER), 0, 500));
+            assert_ok!(Loans::repay(Origin::signed(BORROWER), 0, 5000));
+            assert_eq!(Loans::debt_of(0), Some(0));
+            assert_eq!(Balances::free_balance(LENDER), ENDOWED_BALANCE + 20);
+
+            assert_ok!(Loans::close(Origin::signed(BORROWER), 0));
+            assert_eq!(<Nft as Unique>::owner_of(&asset_id), Some(BORROWER));
+            assert_eq!(Loans::loan(0).unwrap().status, LoanStatus::Closed);
+        });
+    }
+
+    #[test]
+    fn seize_after_default() {
+        new_test_ext().execute_with(|| {
+            let asset_id = request_loan();
+            assert_ok!(Loans::fund(Origin::signed(LENDER), 0));
+
+            // Not matured yet
+            assert_noop!(Loans::seize(Origin::signed(LENDER), 0), Error::<Test>::LoanNotMatured);
+
+            System::set_block_number(11);
+            // Only the lender or admin can seize
+            assert_noop!(Loans::seize(Origin::signed(OTHER), 0), BadOrigin);
+            assert_ok!(Loans::seize(Origin::signed(Admin::get()), 0));
+
+            assert_eq!(<Nft as Unique>::owner_of(&asset_id), Some(LENDER));
+            assert_eq!(Loans::loan(0).unwrap().status, LoanStatus::Seized);
+        });
+    }
+}
@@ -1,5 +1,5 @@
-use crate::nft::{Module, Trait};
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use crate::nft::{self, Module, Trait};
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
@@ -12,6 +12,13 @@ impl_outer_origin! {
     pub enum Origin for Test {}
 }
 
+impl_outer_event! {
+    pub enum MetaEvent for Test {
+        system<T>,
+        nft<T>,
+    }
+}
+
 parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const MaximumBlockWeight: Weight = 1024;
@@ -30,7 +37,7 @@ impl system::Trait for Test {
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = MetaEvent;
     type BlockHashCount = BlockHashCount;
     type MaximumBlockWeight = MaximumBlockWeight;
     type DbWeight = ();
@@ -53,19 +60,35 @@ impl system::Trait for Test {
 #[derive(Clone, Eq, PartialEq)]
 pub struct Test;
 
+parameter_types! {
+    pub const MaxMetadataLength: u32 = 64;
+}
+
 impl Trait for Test {
-    type Event = ();
+    type Event = MetaEvent;
     type AssetInfo = Vec<u8>;
+    type MaxMetadataLength = MaxMetadataLength;
 }
 
 // system under test
 pub type SUT = Module<Test>;
 
+// Returns the most recently deposited nft event
+pub fn last_event() -> MetaEvent {
+    system::Module::<Test>::events()
+        .pop()
+        .map(|e| e.event)
+        .expect("Event expected")
+}
+
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    system::GenesisConfig::default()
+    let t = system::GenesisConfig::default()
         .build_storage::<Test>()
-        .unwrap()
-        .into()
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(t);
+    // Events are not stored in the genesis block
+    ext.execute_with(|| system::Module::<Test>::set_block_number(1));
+    ext
 }
@@ -16,6 +16,7 @@ use frame_system::ensure_signed;
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
     traits::Get,
+    weights::Weight,
     Hashable,
 };
 
@@ -29,8 +30,14 @@ pub trait Trait: frame_system::Trait {
     /// The data type that is used to describe this type of asset.
     type AssetInfo: Hashable + Member + Debug + Default + FullCodec;
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Maximum encoded size of the info of a minted asset
+    type MaxMetadataLength: Get<u32>;
 }
 
+/// Weight of storing a byte of the info of a minted asset, on top of the weight of the call
+/// minting it.
+pub const WEIGHT_PER_METADATA_BYTE: Weight = 10_000;
+
 // A generic definition of an NFT that will be used by this pallet.
 #[derive(Encode, Decode, Clone, RuntimeDebug)]
 pub struct Asset<Hash, AssetInfo> {
@@ -52,13 +59,20 @@ decl_storage! {
     }
 }
 
-// Empty event to satisfy type constraints
 decl_event!(
     pub enum Event<T> where
         <T as frame_system::Trait>::AccountId,
     {
-        /// Ownership of the asset has been transferred to the account.
-        Transferred(RegistryId, AssetId, AccountId),
+        /// A new asset has been minted to the owner account.
+        Minted(RegistryId, AssetId, AccountId),
+        /// Ownership of the asset has been transferred from the first account to the second.
+        Transferred(RegistryId, AssetId, AccountId, AccountId),
+        /// The asset has been locked by its owner (first account) into a custodian account
+        /// (second account), e.g. when it is moved across the bridge.
+        Locked(RegistryId, AssetId, AccountId, AccountId),
+        /// The asset has been released by a custodian account (first account) to the
+        /// second account.
+        Unlocked(RegistryId, AssetId, AccountId, AccountId),
     }
 );
 
@@ -70,6 +84,8 @@ decl_error! {
         NonexistentAsset,
         // Thrown when someone who is not the owner of a asset attempts to transfer or burn it.
         NotAssetOwner,
+        // Thrown when the info of a minted asset exceeds the maximum length.
+        MetadataTooLong,
     }
 }
 
@@ -95,12 +111,59 @@ decl_module! {
             let who = ensure_signed(origin)?;
 
             let asset_id = AssetId(registry_id, token_id);
-            <Self as Unique>::transfer(&who, &dest_account, &asset_id)?;
+            <Self as Unique>::transfer(&who, &dest_account, &asset_id)
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Locks an asset by moving it from its owner into a custodian account, e.g. the
+    /// bridge account. Emits [Event::Locked] instead of [Event::Transferred].
+    pub fn lock(
+        owner: &T::AccountId,
+        custodian: &T::AccountId,
+        asset_id: &AssetId,
+    ) -> dispatch::DispatchResult {
+        Self::change_owner(owner, custodian, asset_id)?;
 
-            Self::deposit_event(RawEvent::Transferred(registry_id, asset_id, dest_account));
+        Self::deposit_event(RawEvent::Locked(asset_id.0, asset_id.clone(), owner.clone(), custodian.clone()));
 
-            Ok(())
-        }
+        Ok(())
+    }
+
+    /// Releases an asset held by a custodian account to the given account.
+    /// Emits [Event::Unlocked] instead of [Event::Transferred].
+    pub fn unlock(
+        custodian: &T::AccountId,
+        dest_account: &T::AccountId,
+        asset_id: &AssetId,
+    ) -> dispatch::DispatchResult {
+        Self::change_owner(custodian, dest_account, asset_id)?;
+
+        Self::deposit_event(RawEvent::Unlocked(asset_id.0, asset_id.clone(), custodian.clone(), dest_account.clone()));
+
+        Ok(())
+    }
+
+    /// Moves an asset from the caller to the destination account, checking that the
+    /// caller owns it. Does not emit any events.
+    fn change_owner(
+        caller: &T::AccountId,
+        dest_account: &T::AccountId,
+        asset_id: &AssetId,
+    ) -> dispatch::DispatchResult {
+        let owner = Self::owner_of(asset_id)
+            .ok_or(Error::<T>::NonexistentAsset)?;
+        let (registry_id, token_id) = AssetIdRef::from(asset_id).destruct();
+
+        // Check that the caller is owner of asset
+        ensure!(caller == &owner,
+                Error::<T>::NotAssetOwner);
+
+        // Replace owner with destination account
+        AccountForAsset::<T>::insert(registry_id, token_id, dest_account);
+
+        Ok(())
     }
 }
 
@@ -120,16 +183,9 @@ impl<T: Trait>
         dest_account: &T::AccountId,
         asset_id: &AssetId,
     ) -> dispatch::DispatchResult {
-        let owner = Self::owner_of(asset_id)
-            .ok_or(Error::<T>::NonexistentAsset)?;
-        let (registry_id, token_id) = AssetIdRef::from(asset_id).destruct();
+        Self::change_owner(caller, dest_account, asset_id)?;
 
-        // Check that the caller is owner of asset
-        ensure!(caller == &owner,
-                Error::<T>::NotAssetOwner);
-
-        // Replace owner with destination account
-        AccountForAsset::<T>::insert(registry_id, token_id, dest_account);
+        Self::deposit_event(RawEvent::Transferred(asset_id.0, asset_id.clone(), caller.clone(), dest_account.clone()));
 
         Ok(())
     }
@@ -156,13 +212,17 @@ impl<T: Trait>
             !AccountForAsset::<T>::contains_key(registry_id, token_id),
             Error::<T>::AssetExists
         );
+        ensure!(
+            asset_info.encoded_size() <= T::MaxMetadataLength::get() as usize,
+            Error::<T>::MetadataTooLong
+        );
 
         // Insert into storage
         AccountForAsset::<T>::insert(registry_id, token_id, owner_account);
 
-        //TODO(mig) Pass metadata on asset_info as soon as we have storage fees
-        let empty_asset: <T as Trait>::AssetInfo = Default::default();
-        Assets::<T>::insert(registry_id, token_id, empty_asset);
+        Assets::<T>::insert(registry_id, token_id, asset_info);
+
+        Self::deposit_event(RawEvent::Minted(*registry_id, asset_id.clone(), owner_account.clone()));
 
         Ok(())
     }
@@ -10,6 +10,8 @@ fn mint() {
         let asset_id = AssetId(H160::zero(), U256::zero());
         let asset_info = vec![];
         assert_ok!(<SUT as Mintable>::mint(&0, &1, &asset_id, asset_info));
+        assert_eq!(last_event(),
+                   MetaEvent::nft(RawEvent::Minted(H160::zero(), asset_id, 1)));
     });
 }
 
@@ -23,6 +25,17 @@ fn mint_err_duplicate_id() {
     });
 }
 
+#[test]
+fn mint_err_metadata_too_long() {
+    new_test_ext().execute_with(|| {
+        let asset_id = AssetId(H160::zero(), U256::zero());
+        // The length prefix adds a byte to the 64 bytes
+        assert_err!(<SUT as Mintable>::mint(&0, &1, &asset_id, vec![0; 64]),
+                    Error::<Test>::MetadataTooLong);
+        assert_ok!(<SUT as Mintable>::mint(&0, &1, &asset_id, vec![0; 63]));
+    });
+}
+
 #[test]
 fn transfer() {
     new_test_ext().execute_with(|| {
@@ -33,6 +46,8 @@ fn transfer() {
         assert_ok!(<SUT as Unique>::transfer(&1, &2, &asset_id));
         // 2 owns asset now
         assert_eq!(<SUT as Unique>::owner_of(&asset_id), Some(2));
+        assert_eq!(last_event(),
+                   MetaEvent::nft(RawEvent::Transferred(H160::zero(), asset_id, 1, 2)));
     });
 }
 
@@ -47,3 +62,26 @@ fn transfer_err_when_not_owner() {
                     Error::<Test>::NotAssetOwner);
     });
 }
+
+#[test]
+fn lock_and_unlock() {
+    new_test_ext().execute_with(|| {
+        let asset_id = AssetId(H160::zero(), U256::zero());
+        // Mint to account 1, custodian is account 3
+        assert_ok!(<SUT as Mintable>::mint(&1, &1, &asset_id, vec![]));
+
+        // Only the owner can lock
+        assert_err!(SUT::lock(&2, &3, &asset_id),
+                    Error::<Test>::NotAssetOwner);
+        assert_ok!(SUT::lock(&1, &3, &asset_id));
+        assert_eq!(<SUT as Unique>::owner_of(&asset_id), Some(3));
+        assert_eq!(last_event(),
+                   MetaEvent::nft(RawEvent::Locked(H160::zero(), asset_id.clone(), 1, 3)));
+
+        // Custodian releases the asset to account 2
+        assert_ok!(SUT::unlock(&3, &2, &asset_id));
+        assert_eq!(<SUT as Unique>::owner_of(&asset_id), Some(2));
+        assert_eq!(last_event(),
+                   MetaEvent::nft(RawEvent::Unlocked(H160::zero(), asset_id, 3, 2)));
+    });
+}
@@ -0,0 +1,75 @@
+use super::*;
+use crate::common;
+use crate::proofs::{hash_of, sort_hash_of};
+use codec::Encode;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Hash, SaturatedConversion};
+
+const SEED: u32 = 0;
+
+fn hash_of_index(i: u32, j: u32) -> H256 {
+    sp_io::hashing::blake2_256(&(i, j).encode()).into()
+}
+
+// Builds `p` proofs with `h` sorted hashes each, together with static proofs they are valid for.
+// No proof can take a shortcut through the hashes of another one: the last hash computed by a
+// proof is the first sorted hash of the previous proof, and the last hash computed by the first
+// proof is the basic data root.
+fn proofs_data(p: u32, h: u32) -> (Vec<Proof>, [H256; 3]) {
+    let mut pfs = Vec::with_capacity(p as usize);
+    let mut root: Option<H256> = None;
+    for i in (0..p).rev() {
+        let leaf = hash_of_index(i, 0);
+        let sorted_hashes: Vec<H256> = (0..h)
+            .map(|j| match (j, root) {
+                (0, Some(root)) => root,
+                _ => hash_of_index(i, j + 1),
+            })
+            .collect();
+        root = Some(sorted_hashes.iter().fold(leaf, |hash, sibling| sort_hash_of(hash, *sibling)));
+        pfs.push(Proof::new(leaf, sorted_hashes));
+    }
+    pfs.reverse();
+
+    let static_proofs = [
+        root.unwrap_or_default(),
+        hash_of_index(p, 1),
+        hash_of_index(p, 2),
+    ];
+    (pfs, static_proofs)
+}
+
+benchmarks! {
+    _ { }
+
+    validate_mint {
+        let p in 1 .. MAX_PROOFS;
+        let h in 1 .. MAX_SORTED_HASHES;
+
+        let caller: T::AccountId = account("caller", 0, SEED);
+        let balance: <T as pallet_balances::Trait>::Balance = (1_000 * crate::constants::currency::CFG).saturated_into();
+        <pallet_balances::Module<T> as Currency<_>>::make_free_balance_be(&caller, balance);
+        <fees::Module<T>>::change_fee(FeeKey::AnchorRentPerDay, 0u32.into());
+        <fees::Module<T>>::change_fee(FeeKey::NftValidate, 1u32.into());
+
+        let (pfs, static_proofs) = proofs_data(p, h);
+        let doc_root = hash_of(hash_of(static_proofs[0], static_proofs[1]), static_proofs[2]);
+        let mut doc_root_hash = T::Hash::default();
+        doc_root_hash.as_mut().copy_from_slice(doc_root.as_ref());
+
+        let pre_image = T::Hashing::hash_of(&0);
+        let anchor_id = pre_image.using_encoded(T::Hashing::hash);
+        <anchor::Module<T>>::commit(
+            RawOrigin::Signed(caller.clone()).into(),
+            pre_image,
+            doc_root_hash,
+            T::Hashing::hash_of(&0),
+            (common::MS_PER_DAY + 1).saturated_into(),
+        )?;
+
+        let dest_id: chainbridge::ChainId = 0;
+        <chainbridge::Module<T>>::whitelist(dest_id)?;
+    }: _(RawOrigin::Signed(caller), anchor_id, [0; 20], pfs, static_proofs, dest_id)
+}
@@ -1,22 +1,40 @@
 use crate::bridge as pallet_bridge;
-use crate::{anchor, fees, proofs, proofs::Proof};
-use frame_support::{decl_event, decl_module, dispatch::DispatchResult, ensure, traits::Get};
+use crate::{anchor, fees::{self, FeeKey}, proofs, proofs::Proof, va_registry};
+use crate::va_registry::types::{AssetId, AssetInfo, MintInfo, RegistryId, TokenId, VerifierRegistry};
+use frame_support::{
+    decl_event, decl_module, dispatch::DispatchResult, ensure,
+    traits::{EnsureOrigin, Get},
+    weights::Weight,
+};
 use frame_system::ensure_signed;
 use sp_core::H256;
 use sp_std::vec::Vec;
-use crate::constants::currency;
-use sp_runtime::traits::SaturatedConversion;
 
-/// Additional Fee charged to validate NFT proofs
-const NFT_FEE: u128 = 10 * currency::CFG;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
 
-pub trait Trait: anchor::Trait + pallet_balances::Trait + pallet_bridge::Trait {
+pub use weights::WeightInfo;
+
+/// Maximum number of proofs accepted by a single mint.
+pub const MAX_PROOFS: u32 = 64;
+/// Maximum number of sorted hashes accepted in a single proof.
+pub const MAX_SORTED_HASHES: u32 = 32;
+
+pub trait Trait: anchor::Trait + pallet_balances::Trait + pallet_bridge::Trait + va_registry::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Weights of the calls, see [weights::SubstrateWeight]
+    type WeightInfo: WeightInfo;
 }
 
 decl_event!(
-    pub enum Event<T> where <T as frame_system::Trait>::Hash {
+    pub enum Event<T> where
+        <T as frame_system::Trait>::Hash,
+        <T as frame_system::Trait>::AccountId,
+    {
         DepositAsset(Hash),
+        /// The nft of the document proven by the bundled hash was minted to the account
+        AssetReceived(Hash, RegistryId, TokenId, AccountId),
     }
 );
 
@@ -30,11 +48,13 @@ decl_module! {
         ///
         /// Adds additional fee to compensate the current cost of target chains
         /// # <weight>
-        /// - depends on the arguments
+        /// - linear in the number of proofs and the largest number of sorted hashes of a proof
         /// # </weight>
-        #[weight = 120_000_000]
+        #[weight = proofs_weight::<T>(pfs.iter().map(|proof| proof.sorted_hashes().len()))
+            .saturating_add(<fees::Module<T>>::charge_weight())]
         fn validate_mint(origin, anchor_id: T::Hash, deposit_address: [u8; 20], pfs: Vec<Proof>, static_proofs: [H256;3], dest_id: chainbridge::ChainId) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            ensure!(Self::proofs_within_bounds(pfs.iter().map(|proof| proof.sorted_hashes().len())), "Too many proofs or hashes");
 
             // get the anchor data from anchor ID
             let anchor_data = <anchor::Module<T>>::get_anchor_by_id(anchor_id).ok_or("Anchor doesn't exist")?;
@@ -49,17 +69,66 @@ decl_module! {
             let metadata = bundled_hash.as_ref().to_vec();
             let resource_id = <T as pallet_bridge::Trait>::HashId::get();
 
-			// Burn additional fees
-			let nft_fee: T::Balance = NFT_FEE.saturated_into();
-            <fees::Module<T>>::burn_fee(&who, nft_fee)?;
+			// Pay additional fees
+            <fees::Module<T>>::pay_fee(who, FeeKey::NftValidate)?;
 
             <chainbridge::Module<T>>::transfer_generic(dest_id, resource_id, metadata)?;
             Ok(())
         }
+
+        /// Inbound counterpart of [validate_mint], executed by the bridge. Receives the proofs
+        /// deposited on another chain along with their deposit address. The bundled hash is
+        /// computed from them and stored as the metadata of the nft, which is minted to the
+        /// recipient by the registry once the proofs are validated against the local anchor.
+        ///
+        /// # <weight>
+        /// - linear in the number of proofs and the largest number of sorted hashes of a proof
+        /// - the proofs are hashed like in [validate_mint], whose weight bounds the storage
+        ///   accesses of the registry
+        /// # </weight>
+        #[weight = proofs_weight::<T>(mint_info.proofs.iter().map(|proof| proof.hashes.len()))]
+        fn receive_document(origin,
+                            recipient: T::AccountId,
+                            deposit_address: [u8; 20],
+                            registry_id: RegistryId,
+                            token_id: TokenId,
+                            mint_info: MintInfo<T::Hash, H256>,
+        ) -> DispatchResult {
+            let bridge_id = <T as pallet_bridge::Trait>::BridgeOrigin::ensure_origin(origin)?;
+            ensure!(Self::proofs_within_bounds(mint_info.proofs.iter().map(|proof| proof.hashes.len())), "Too many proofs or hashes");
+
+            // the bundled hash commits to the proofs and the deposit address
+            let pfs = mint_info.proofs.iter().cloned().map(Proof::from).collect();
+            let bundled_hash = Self::get_bundled_hash(pfs, deposit_address);
+
+            // the registry validates the proofs against the local anchor
+            let asset_id = AssetId(registry_id, token_id);
+            let asset_info = AssetInfo { metadata: bundled_hash.as_ref().to_vec() };
+            <va_registry::Module<T> as VerifierRegistry>::mint(&bridge_id, &recipient, &asset_id, asset_info, mint_info)?;
+
+            Self::deposit_event(RawEvent::AssetReceived(bundled_hash, registry_id, token_id, recipient));
+            Ok(())
+        }
     }
 }
 
+/// Weight of the benchmarked [validate_mint] for proofs with the given numbers of sorted hashes,
+/// i.e. of their number and the largest number of sorted hashes of a proof. Both are clamped to
+/// [MAX_PROOFS] and [MAX_SORTED_HASHES], as larger inputs are rejected before any hashing.
+fn proofs_weight<T: Trait>(sorted_hashes: impl ExactSizeIterator<Item = usize>) -> Weight {
+    let proofs = sorted_hashes.len().min(MAX_PROOFS as usize) as u32;
+    let hashes = sorted_hashes.max().unwrap_or(0).min(MAX_SORTED_HASHES as usize) as u32;
+    <T as Trait>::WeightInfo::validate_mint(proofs, hashes)
+}
+
 impl<T: Trait + pallet_bridge::Trait> Module<T> {
+    /// Returns true if there are at most [MAX_PROOFS] proofs with at most [MAX_SORTED_HASHES]
+    /// sorted hashes each.
+    fn proofs_within_bounds(mut sorted_hashes: impl ExactSizeIterator<Item = usize>) -> bool {
+        sorted_hashes.len() <= MAX_PROOFS as usize
+            && sorted_hashes.all(|hashes| hashes <= MAX_SORTED_HASHES as usize)
+    }
+
     /// Validates the proofs again the provided doc_root.
     /// returns false if any proofs are invalid.
     fn validate_proofs(doc_root: T::Hash, pfs: &Vec<Proof>, static_proofs: [H256; 3]) -> bool {
@@ -80,6 +149,7 @@ mod tests {
     use super::*;
 
     use crate::common;
+    use crate::constants::currency;
     use crate::fees;
     use crate::nft;
     use crate::nfts;
@@ -92,7 +162,7 @@ mod tests {
     //use chainbridge::ResourceId;
     use frame_system::EnsureSignedBy;
     use sp_core::hashing::blake2_128;
-    use sp_core::H256;
+    use sp_core::{H160, H256, U256};
     use sp_runtime::{
         testing::Header,
         traits::{BadOrigin, BlakeTwo256, Block as BlockT, Hash, IdentityLookup},
@@ -159,6 +229,7 @@ mod tests {
 
     impl Trait for Test {
         type Event = ();
+        type WeightInfo = ();
     }
 
     parameter_types! {
@@ -176,9 +247,17 @@ mod tests {
         type AdminOrigin = EnsureSignedBy<One, u64>;
     }
 
+    parameter_types! {
+        pub const MaxMetadataLength: u32 = 1024;
+    }
     impl nft::Trait for Test {
         type Event = ();
         type AssetInfo = crate::va_registry::types::AssetInfo;
+        type MaxMetadataLength = MaxMetadataLength;
+    }
+
+    impl va_registry::Trait for Test {
+        type Event = ();
     }
 
     parameter_types! {
@@ -215,6 +294,13 @@ mod tests {
     impl fees::Trait for Test {
         type Event = ();
         type FeeChangeOrigin = frame_system::EnsureRoot<u64>;
+        type Author = ();
+        type TreasuryAccount = ();
+        type StakingPotAccount = ();
+        type CreditAccount = ();
+        type SpendOrigin = frame_system::EnsureRoot<u64>;
+        type MaxFeeHistory = ();
+        type CfgPrice = ();
     }
 
     parameter_types! {
@@ -229,9 +315,17 @@ mod tests {
         type WeightInfo = ();
     }
 
+    impl crate::assets::Trait for Test {
+        type Event = ();
+        type Balance = u128;
+        type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
+    }
+
     impl bridge_mapping::Trait for Test {
+        type Event = ();
         type ResourceId = crate::bridge::ResourceId;
         type Address = crate::bridge::Address;
+        type AssetId = crate::assets::AssetId;
         type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
     }
 
@@ -241,15 +335,16 @@ mod tests {
             .build_storage::<Test>()
             .unwrap();
         fees::GenesisConfig::<Test> {
-            initial_fees: vec![(
-                // anchoring state rent fee per day
-                H256::from(&[
-                    17, 218, 109, 31, 118, 29, 223, 155, 219, 76, 157, 110, 83, 3, 235, 212, 31,
-                    97, 133, 141, 10, 86, 71, 161, 167, 191, 224, 137, 191, 146, 27, 233,
-                ]),
-                // state rent 0 for tests
-                0,
-            )],
+            destinations: vec![],
+            initial_fees: vec![
+                (
+                    // anchoring state rent fee per day
+                    FeeKey::AnchorRentPerDay,
+                    // state rent 0 for tests
+                    0,
+                ),
+                (FeeKey::NftValidate, 10 * currency::CFG),
+            ],
         }
         .assimilate_storage(&mut t)
         .unwrap();
@@ -401,6 +496,31 @@ mod tests {
         })
     }
 
+    #[test]
+    fn too_many_proofs_or_hashes() {
+        new_test_ext().execute_with(|| {
+            let (anchor_id, deposit_address, _, static_proofs, chain_id) = get_params();
+            let proof = |hashes| Proof::new([1; 32].into(), vec![[2; 32].into(); hashes]);
+
+            let pfs = vec![proof(1); MAX_PROOFS as usize + 1];
+            assert_err!(
+                Nfts::validate_mint(Origin::signed(USER_A), anchor_id, deposit_address, pfs, static_proofs, chain_id),
+                "Too many proofs or hashes"
+            );
+            let pfs = vec![proof(MAX_SORTED_HASHES as usize + 1)];
+            assert_err!(
+                Nfts::validate_mint(Origin::signed(USER_A), anchor_id, deposit_address, pfs, static_proofs, chain_id),
+                "Too many proofs or hashes"
+            );
+            // Within the bounds the anchor is looked up
+            let pfs = vec![proof(MAX_SORTED_HASHES as usize); MAX_PROOFS as usize];
+            assert_err!(
+                Nfts::validate_mint(Origin::signed(USER_A), anchor_id, deposit_address, pfs, static_proofs, chain_id),
+                "Anchor doesn't exist"
+            );
+        })
+    }
+
     #[test]
     fn missing_anchor() {
         new_test_ext().execute_with(|| {
@@ -514,4 +634,49 @@ mod tests {
             assert_eq!(account_current_balance, 90 * currency::CFG);
         })
     }
+
+    #[test]
+    fn receive_document() {
+        new_test_ext().execute_with(|| {
+            let recipient = 2;
+            let deposit_address: [u8; 20] = [1; 20];
+            let (asset_id, pre_image, anchor_id, (proofs, static_hashes, doc_root), _, _) =
+                va_registry::tests::setup_mint::<Test>(USER_A, U256::one());
+            let (registry_id, token_id) = asset_id.clone().destruct();
+            assert_ok!(Anchor::commit(
+                Origin::signed(2),
+                pre_image,
+                doc_root,
+                <Test as frame_system::Trait>::Hashing::hash_of(&0),
+                common::MS_PER_DAY + 1
+            ));
+
+            let pfs = proofs.iter().cloned().map(Proof::from).collect();
+            let bundled_hash = Nfts::get_bundled_hash(pfs, deposit_address);
+            let mint_info = MintInfo { anchor_id, static_hashes, proofs };
+            let bridge_origin = || Origin::signed(<chainbridge::Module<Test>>::account_id());
+
+            assert_err!(
+                Nfts::receive_document(Origin::signed(USER_A), recipient, deposit_address,
+                                       registry_id, token_id, mint_info.clone()),
+                BadOrigin
+            );
+            // The proofs must match the anchored document
+            let mut other_anchor = mint_info.clone();
+            other_anchor.anchor_id = <Test as frame_system::Trait>::Hashing::hash_of(&1);
+            assert_err!(
+                Nfts::receive_document(bridge_origin(), recipient, deposit_address,
+                                       registry_id, token_id, other_anchor),
+                va_registry::Error::<Test>::DocumentNotAnchored
+            );
+
+            assert_ok!(Nfts::receive_document(bridge_origin(), recipient, deposit_address,
+                                              registry_id, token_id, mint_info));
+            assert_eq!(<nft::Module<Test>>::account_for_asset::<H160, U256>(registry_id, token_id), Some(recipient));
+            assert_eq!(
+                <nft::Module<Test>>::asset(registry_id, token_id).map(|info| info.metadata),
+                Some(bundled_hash.as_ref().to_vec())
+            );
+        })
+    }
 }
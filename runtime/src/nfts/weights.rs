@@ -0,0 +1,33 @@
+//! Weights of the nfts module, from the `validate_mint` benchmark of [super::benchmarking].
+//!
+//! The benchmark varies one component while the other one is at its maximum, so the cost of
+//! every proof includes [super::MAX_SORTED_HASHES] hashes and the cost of every sorted hash is
+//! paid for [super::MAX_PROOFS] proofs.
+
+#![allow(unused_parens)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions of the nfts module.
+pub trait WeightInfo {
+    fn validate_mint(p: u32, h: u32, ) -> Weight;
+}
+
+/// Weights of the nfts module using the database weights of the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+    fn validate_mint(p: u32, h: u32, ) -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add((67_000_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add((128_000_000 as Weight).saturating_mul(h as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+}
+
+impl WeightInfo for () {
+    fn validate_mint(_p: u32, _h: u32, ) -> Weight {
+        1_000_000_000
+    }
+}
@@ -0,0 +1,361 @@
+//! # Price Oracle
+//!
+//! Authorized feeders submit the price of CFG in USD. The oracle price is the median of all
+//! feeds that are not older than [Trait::MaxPriceAge] blocks. With fewer than
+//! [Trait::MinFeeders] fresh feeds there is no oracle price, so consumers can fall back to a fixed
+//! CFG amount. At most [Trait::MaxFeeders] accounts are authorized, which bounds the feeds read to
+//! compute the price.
+
+use crate::fees;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::{EnsureOrigin, Get},
+    weights::Weight,
+};
+use frame_system::{ensure_root, ensure_signed};
+use sp_runtime::{
+    traits::{CheckedDiv, Saturating, Zero},
+    FixedPointNumber, FixedU128,
+};
+use sp_std::prelude::*;
+
+pub trait Trait: frame_system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+    /// Origin that can authorize and remove feeders
+    type AdminOrigin: EnsureOrigin<Self::Origin>;
+    /// Number of blocks after which a feed is stale and no longer part of the oracle price
+    type MaxPriceAge: Get<Self::BlockNumber>;
+    /// Minimum number of fresh feeds for an oracle price
+    type MinFeeders: Get<u32>;
+    /// Maximum number of accounts authorized to feed prices
+    type MaxFeeders: Get<u32>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Oracle {
+        /// Accounts authorized to feed prices
+        Feeders get(fn is_feeder): map hasher(blake2_128_concat) T::AccountId => bool;
+
+        /// Number of accounts authorized to feed prices, bounded by [Trait::MaxFeeders]
+        FeederCount get(fn feeder_count): u32;
+
+        /// Latest USD price of one CFG submitted by a feeder, with the block it was submitted at
+        Feeds get(fn feed_of): map hasher(blake2_128_concat) T::AccountId => Option<(FixedU128, T::BlockNumber)>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+    {
+        /// The account has been authorized to feed prices
+        FeederAdded(AccountId),
+        /// The account is no longer authorized to feed prices and its feed has been removed
+        FeederRemoved(AccountId),
+        /// The feeder submitted a new USD price of one CFG
+        PriceFed(AccountId, FixedU128),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// The caller is not an authorized feeder
+        NotFeeder,
+        /// A price of zero is not a valid price
+        ZeroPrice,
+        /// The maximum number of feeders is already authorized
+        TooManyFeeders,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+        fn deposit_event() = default;
+
+        const MaxPriceAge: T::BlockNumber = T::MaxPriceAge::get();
+        const MinFeeders: u32 = T::MinFeeders::get();
+        const MaxFeeders: u32 = T::MaxFeeders::get();
+
+        /// Authorize the account to feed prices
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2, 2) + 195_000_000]
+        pub fn add_feeder(origin, who: T::AccountId) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            if !Self::is_feeder(&who) {
+                let count = Self::feeder_count();
+                ensure!(count < T::MaxFeeders::get(), Error::<T>::TooManyFeeders);
+                FeederCount::put(count + 1);
+                <Feeders<T>>::insert(&who, true);
+            }
+
+            Self::deposit_event(RawEvent::FeederAdded(who));
+            Ok(())
+        }
+
+        /// Remove the authorization of the account to feed prices, along with its last feed
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(2, 3) + 195_000_000]
+        pub fn remove_feeder(origin, who: T::AccountId) -> DispatchResult {
+            Self::ensure_admin(origin)?;
+            if Self::is_feeder(&who) {
+                FeederCount::mutate(|count| *count = count.saturating_sub(1));
+                <Feeders<T>>::remove(&who);
+            }
+            <Feeds<T>>::remove(&who);
+
+            Self::deposit_event(RawEvent::FeederRemoved(who));
+            Ok(())
+        }
+
+        /// Submit the current USD price of one CFG
+        ///
+        /// # <weight>
+        /// - Independent of the arguments.
+        /// # </weight>
+        #[weight = T::DbWeight::get().reads_writes(1, 1) + 195_000_000]
+        pub fn feed(origin, price: FixedU128) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::is_feeder(&who), Error::<T>::NotFeeder);
+            ensure!(!price.is_zero(), Error::<T>::ZeroPrice);
+
+            let now = <frame_system::Module<T>>::block_number();
+            <Feeds<T>>::insert(&who, (price, now));
+
+            Self::deposit_event(RawEvent::PriceFed(who, price));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Returns the median of all fresh feeds, or None if there are fewer than [Trait::MinFeeders]
+    pub fn price() -> Option<FixedU128> {
+        let now = <frame_system::Module<T>>::block_number();
+        let mut prices = <Feeds<T>>::iter()
+            .map(|(_, feed)| feed)
+            .filter(|(_, fed_at)| now.saturating_sub(*fed_at) <= T::MaxPriceAge::get())
+            .map(|(price, _)| price)
+            .collect::<Vec<_>>();
+        if prices.is_empty() || prices.len() < T::MinFeeders::get() as usize {
+            return None;
+        }
+
+        prices.sort();
+        let mid = prices.len() / 2;
+        if prices.len() % 2 == 0 {
+            prices[mid - 1]
+                .saturating_add(prices[mid])
+                .checked_div(&FixedU128::saturating_from_integer(2))
+        } else {
+            Some(prices[mid])
+        }
+    }
+
+    /// Ensure that the given origin is either root or the [AdminOrigin]
+    fn ensure_admin(origin: T::Origin) -> DispatchResult {
+        T::AdminOrigin::try_origin(origin)
+            .map(|_| ())
+            .or_else(ensure_root)?;
+
+        Ok(())
+    }
+}
+
+impl<T: Trait> fees::CfgPrice for Module<T> {
+    fn usd_per_cfg() -> Option<FixedU128> {
+        Self::price()
+    }
+
+    fn weight() -> Weight {
+        // There is at most one feed per feeder
+        T::DbWeight::get().reads(T::MaxFeeders::get() as Weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{
+        assert_noop, assert_ok, impl_outer_origin, ord_parameter_types, parameter_types,
+    };
+    use frame_system::EnsureSignedBy;
+    use sp_core::H256;
+    use std::cell::RefCell;
+    use sp_runtime::{
+        testing::Header,
+        traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+        Perbill,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test where system = frame_system {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl frame_system::Trait for Test {
+        type BaseCallFilter = ();
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type DbWeight = ();
+        type BlockExecutionWeight = ();
+        type ExtrinsicBaseWeight = ();
+        type MaximumExtrinsicWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+        type ModuleToIndex = ();
+        type AccountData = ();
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+    }
+
+    ord_parameter_types! {
+        pub const Admin: u64 = 9;
+    }
+    parameter_types! {
+        pub const MaxPriceAge: u64 = 10;
+        pub const MaxFeeders: u32 = 4;
+    }
+    thread_local! {
+        static MIN_FEEDERS: RefCell<u32> = RefCell::new(1);
+    }
+    pub struct MinFeeders;
+    impl Get<u32> for MinFeeders {
+        fn get() -> u32 {
+            MIN_FEEDERS.with(|v| *v.borrow())
+        }
+    }
+    impl Trait for Test {
+        type Event = ();
+        type AdminOrigin = EnsureSignedBy<Admin, u64>;
+        type MaxPriceAge = MaxPriceAge;
+        type MinFeeders = MinFeeders;
+        type MaxFeeders = MaxFeeders;
+    }
+
+    type Oracle = Module<Test>;
+    type System = frame_system::Module<Test>;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let t = frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    fn price(n: u128) -> FixedU128 {
+        FixedU128::saturating_from_rational(n, 100)
+    }
+
+    #[test]
+    fn only_feeders_can_feed() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(Oracle::add_feeder(Origin::signed(1), 1), BadOrigin);
+            assert_noop!(Oracle::feed(Origin::signed(1), price(50)), Error::<Test>::NotFeeder);
+
+            assert_ok!(Oracle::add_feeder(Origin::signed(Admin::get()), 1));
+            assert_noop!(Oracle::feed(Origin::signed(1), price(0)), Error::<Test>::ZeroPrice);
+            assert_ok!(Oracle::feed(Origin::signed(1), price(50)));
+            assert_eq!(Oracle::price(), Some(price(50)));
+
+            // Removing a feeder removes its feed
+            assert_ok!(Oracle::remove_feeder(Origin::root(), 1));
+            assert_noop!(Oracle::feed(Origin::signed(1), price(50)), Error::<Test>::NotFeeder);
+            assert_eq!(Oracle::price(), None);
+        });
+    }
+
+    #[test]
+    fn price_is_median_of_fresh_feeds() {
+        new_test_ext().execute_with(|| {
+            for feeder in 1..=4 {
+                assert_ok!(Oracle::add_feeder(Origin::root(), feeder));
+            }
+            assert_eq!(Oracle::price(), None);
+
+            assert_ok!(Oracle::feed(Origin::signed(1), price(10)));
+            System::set_block_number(5);
+            assert_ok!(Oracle::feed(Origin::signed(2), price(50)));
+            assert_ok!(Oracle::feed(Origin::signed(3), price(40)));
+            assert_ok!(Oracle::feed(Origin::signed(4), price(1000)));
+
+            // Median of an even number of feeds is the mean of the middle two
+            assert_eq!(Oracle::price(), Some(price(45)));
+
+            // First feed is stale
+            System::set_block_number(12);
+            assert_eq!(Oracle::price(), Some(price(50)));
+
+            // All feeds are stale
+            System::set_block_number(16);
+            assert_eq!(Oracle::price(), None);
+        });
+    }
+
+    #[test]
+    fn feeders_are_bounded() {
+        new_test_ext().execute_with(|| {
+            for feeder in 1..=4 {
+                assert_ok!(Oracle::add_feeder(Origin::root(), feeder));
+            }
+            // Adding a feeder again does not count twice
+            assert_ok!(Oracle::add_feeder(Origin::root(), 4));
+            assert_eq!(Oracle::feeder_count(), 4);
+            assert_noop!(Oracle::add_feeder(Origin::root(), 5), Error::<Test>::TooManyFeeders);
+
+            assert_ok!(Oracle::remove_feeder(Origin::root(), 4));
+            assert_ok!(Oracle::remove_feeder(Origin::root(), 4));
+            assert_eq!(Oracle::feeder_count(), 3);
+            assert_ok!(Oracle::add_feeder(Origin::root(), 5));
+        });
+    }
+
+    #[test]
+    fn price_requires_min_feeders() {
+        new_test_ext().execute_with(|| {
+            MIN_FEEDERS.with(|v| *v.borrow_mut() = 2);
+            for feeder in 1..=2 {
+                assert_ok!(Oracle::add_feeder(Origin::root(), feeder));
+            }
+
+            assert_ok!(Oracle::feed(Origin::signed(1), price(10)));
+            assert_eq!(Oracle::price(), None);
+            assert_ok!(Oracle::feed(Origin::signed(2), price(20)));
+            assert_eq!(Oracle::price(), Some(price(15)));
+
+            // Only one feed is still fresh
+            System::set_block_number(5);
+            assert_ok!(Oracle::feed(Origin::signed(2), price(20)));
+            System::set_block_number(12);
+            assert_eq!(Oracle::price(), None);
+            MIN_FEEDERS.with(|v| *v.borrow_mut() = 1);
+        });
+    }
+}
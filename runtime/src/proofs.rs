@@ -17,6 +17,10 @@ impl Proof {
             sorted_hashes,
         }
     }
+
+    pub fn sorted_hashes(&self) -> &[H256] {
+        &self.sorted_hashes
+    }
 }
 
 /// Validates each proof and return true if all the proofs are valid else returns false
@@ -51,7 +55,7 @@ pub fn validate_proofs(doc_root: H256, proofs: &Vec<Proof>, static_proofs: [H256
 // computes blake2 256 sorted hash of the a and b
 // if a < b: blake256(a+b)
 // else: blake256(b+a)
-fn sort_hash_of(a: H256, b: H256) -> H256 {
+pub(crate) fn sort_hash_of(a: H256, b: H256) -> H256 {
     let mut h: Vec<u8> = Vec::with_capacity(64);
     if a < b {
         h.extend_from_slice(&a[..]);
@@ -65,7 +69,7 @@ fn sort_hash_of(a: H256, b: H256) -> H256 {
 }
 
 // computes blake2 256 hash of the a + b
-fn hash_of(a: H256, b: H256) -> H256 {
+pub(crate) fn hash_of(a: H256, b: H256) -> H256 {
     let mut h: Vec<u8> = Vec::with_capacity(64);
     h.extend_from_slice(&a[..]);
     h.extend_from_slice(&b[..]);
@@ -1,15 +1,18 @@
-use sp_core::{Encode};
+use codec::{Decode, Encode};
 use sp_runtime::traits::{Hash, SaturatedConversion};
 use frame_system::{ensure_none, ensure_root, ensure_signed};
 use crate::constants::currency;
 use sp_std::{vec::Vec, convert::TryInto};
-use frame_support::{decl_module, decl_storage, decl_event, decl_error,
-                    traits::{Get, EnsureOrigin, Currency, ExistenceRequirement::KeepAlive},
-                    weights::{DispatchClass, Pays},
+#[cfg(feature = "std")]
+use serde::Serialize;
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, Parameter,
+                    storage::migration,
+                    traits::{Get, EnsureOrigin, Currency, ExistenceRequirement::KeepAlive, VestingSchedule},
+                    weights::{DispatchClass, Pays, Weight},
                     ensure, dispatch::DispatchResult};
 use sp_runtime::{
-    ModuleId,
-    traits::{AccountIdConversion, CheckedSub},
+    DispatchError, ModuleId, PerThing, Perbill, RuntimeDebug,
+    traits::{AccountIdConversion, CheckedSub, IdentifyAccount, Saturating, Verify, Zero},
     transaction_validity::{
         TransactionValidity, ValidTransaction, InvalidTransaction, TransactionSource,
         TransactionPriority,
@@ -17,8 +20,111 @@ use sp_runtime::{
 };
 
 const MODULE_ID: ModuleId = ModuleId(*b"rd/claim");
+/// Minimum payout of the program that existed before programs were introduced
 const MIN_PAYOUT: node_primitives::Balance = 5 * currency::CFG;
 
+/// Identifier of a reward program
+pub type ProgramId = u32;
+
+/// Program that the claims made before programs were introduced are migrated to
+pub const LEGACY_PROGRAM: ProgramId = 0;
+
+/// Prefix of the payload signed by the account of a leaf to authorize the payout to a beneficiary
+const CLAIM_TO_PREFIX: &[u8] = b"rad_claims:claim_to";
+
+/// A reward program whose rewards are claimed against its root hashes
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct Program<AccountId, Balance, BlockNumber> {
+    /// Account that is allowed to upload root hashes of the program
+    pub uploader: AccountId,
+    /// Minimum amount paid out by a claim
+    pub min_payout: Balance,
+    /// Last block at which rewards of the program can be claimed, if any
+    pub expiry: Option<BlockNumber>,
+}
+
+/// A root hash of a program that rewards can be claimed against
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display, BlockNumber: Serialize")))]
+pub struct RootHashInfo<Balance, BlockNumber> {
+    /// Block at which the root hash was uploaded
+    pub uploaded_at: BlockNumber,
+    /// Total of the cumulative amounts of the tree, as declared by the uploader
+    #[cfg_attr(feature = "std", serde(serialize_with = "crate::fees::serialize_as_string"))]
+    pub committed: Balance,
+    /// Last block at which rewards can be claimed against the root hash, if any
+    pub expiry: Option<BlockNumber>,
+    /// Vesting of the rewards claimed against the root hash, paid out liquid if none
+    pub vesting: Option<ClaimVesting<BlockNumber>>,
+}
+
+/// Vesting of the rewards claimed against a root hash. The vested part of a payout is locked by
+/// a vesting schedule of the beneficiary, like a vested transfer, the rest is liquid.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ClaimVesting<BlockNumber> {
+    /// Part of a payout that vests
+    pub vested: Perbill,
+    /// Number of blocks after the claim before the vested part starts to unlock
+    pub cliff: BlockNumber,
+    /// Part of the vested part that unlocks per block after the cliff
+    pub per_block: Perbill,
+}
+
+impl<Balance, BlockNumber: PartialOrd> RootHashInfo<Balance, BlockNumber> {
+    /// Returns true if rewards can still be claimed against the root hash at the given block
+    pub fn is_active(&self, now: &BlockNumber) -> bool {
+        self.expiry.as_ref().map_or(true, |expiry| now <= expiry)
+    }
+}
+
+/// A claim of the rewards of a leaf whose payout the account of the leaf authorized to a
+/// beneficiary by signing the [Module::claim_to_payload]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ClaimTo<AccountId, Balance, Hash, Signature> {
+    /// Account of the leaf
+    pub account_id: AccountId,
+    /// Cumulative amount of the leaf
+    pub amount: Balance,
+    /// Proof of the leaf
+    pub sorted_hashes: Vec<Hash>,
+    /// Account the payout is transferred to
+    pub beneficiary: AccountId,
+    /// Signature of the account of the leaf
+    pub signature: Signature,
+}
+
+/// Funding of a program
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize))]
+#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+pub struct ProgramFunding<Balance> {
+    /// Free balance of the funding account of the program
+    #[cfg_attr(feature = "std", serde(serialize_with = "crate::fees::serialize_as_string"))]
+    pub balance: Balance,
+    /// Rewards committed by the active root hashes of the program that were not claimed yet
+    #[cfg_attr(feature = "std", serde(serialize_with = "crate::fees::serialize_as_string"))]
+    pub liability: Balance,
+}
+
+type RootHashInfoOf<T> = RootHashInfo<<T as pallet_balances::Trait>::Balance, <T as frame_system::Trait>::BlockNumber>;
+
+type ClaimToOf<T> = ClaimTo<
+    <T as frame_system::Trait>::AccountId,
+    <T as pallet_balances::Trait>::Balance,
+    <T as frame_system::Trait>::Hash,
+    <T as Trait>::Signature,
+>;
+
+type ProgramOf<T> = Program<
+    <T as frame_system::Trait>::AccountId,
+    <T as pallet_balances::Trait>::Balance,
+    <T as frame_system::Trait>::BlockNumber,
+>;
+
 pub trait Trait: frame_system::Trait + pallet_balances::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
@@ -36,17 +142,33 @@ pub trait Trait: frame_system::Trait + pallet_balances::Trait {
     type AdminOrigin: EnsureOrigin<Self::Origin>;
 
     type Currency: Currency<Self::AccountId>;
+
+    /// Signature of the account of a leaf authorizing the payout to a beneficiary
+    type Signature: Parameter + Verify<Signer = Self::Signer>;
+
+    /// Signer of a [Trait::Signature], identifying the account of a leaf
+    type Signer: IdentifyAccount<AccountId = Self::AccountId>;
+
+    /// Vesting schedules of the vested part of payouts
+    type Vesting: VestingSchedule<Self::AccountId, Moment = Self::BlockNumber, Currency = pallet_balances::Module<Self>>;
+
+    /// Minimum vested part of a payout, like the minimum of a vested transfer
+    type MinVestedTransfer: Get<Self::Balance>;
 }
 
 decl_storage! {
     // RadClaims is essentially the CFG Token Claim, just with the deprecated name of the CFG token
     trait Store for Module<T: Trait> as RadClaims {
-        /// Total unclaimed rewards for an account.
-        AccountBalances get(fn get_account_balance): map hasher(blake2_128_concat) T::AccountId => T::Balance = 0.into();
-        /// Map of root hashes that correspond to lists of CFG reward claim amounts per account.
-        RootHashes get(fn get_root_hash): map hasher(blake2_128_concat) T::Hash => bool;
-        /// Account that is allowed to upload new root hashes.
-        UploadAccount get(fn get_upload_account): T::AccountId;
+        /// Reward programs by id.
+        Programs get(fn program): map hasher(twox_64_concat) ProgramId => Option<ProgramOf<T>>;
+        /// Total rewards of a program claimed by an account.
+        AccountBalances get(fn get_account_balance): double_map hasher(twox_64_concat) ProgramId, hasher(blake2_128_concat) T::AccountId => T::Balance = 0.into();
+        /// Map of root hashes of a program that correspond to lists of CFG reward claim amounts per account.
+        RootHashes get(fn get_root_hash): double_map hasher(twox_64_concat) ProgramId, hasher(blake2_128_concat) T::Hash => Option<RootHashInfoOf<T>>;
+        /// Root hashes of a program that were revoked and can't be stored again.
+        RevokedRootHashes get(fn is_revoked): double_map hasher(twox_64_concat) ProgramId, hasher(blake2_128_concat) T::Hash => bool;
+        /// Total rewards of a program paid out by claims.
+        TotalClaimed get(fn total_claimed): map hasher(twox_64_concat) ProgramId => T::Balance = 0.into();
     }
 }
 
@@ -54,12 +176,28 @@ decl_error! {
     pub enum Error for Module<T: Trait>{
         /// The combination of account id, amount, and proofs vector in a claim was invalid.
         InvalidProofs,
-        /// The payout amount attempting to be claimed is less than the minimum allowed by [MIN_PAYOUT].
+        /// The payout amount attempting to be claimed is less than the minimum payout of the program.
         UnderMinPayout,
         /// Amount being claimed is less than the available amount in [AccountBalances].
         InsufficientBalance,
         /// Protected operation, must be performed by admin
         MustBeAdmin,
+        /// No program with the id exists
+        UnknownProgram,
+        /// Rewards of the program can no longer be claimed
+        ProgramExpired,
+        /// No root hash of the program matches
+        UnknownRootHash,
+        /// The signature of the account of the leaf does not authorize the payout to the beneficiary
+        InvalidSignature,
+        /// The vested part of the payout is less than the minimum of a vested transfer
+        VestedAmountLow,
+        /// The beneficiary already has a vesting schedule, the vested part can't be paid out
+        ExistingVestingSchedule,
+        /// The root hash was already stored for the program
+        RootHashExists,
+        /// The root hash was revoked for the program and can't be stored again
+        RootHashWasRevoked,
     }
 }
 
@@ -68,9 +206,22 @@ decl_event! {
         <T as frame_system::Trait>::AccountId,
         <T as frame_system::Trait>::Hash,
         <T as pallet_balances::Trait>::Balance,
+        <T as frame_system::Trait>::BlockNumber,
     {
-        Claimed(AccountId, Balance),
-        RootHashStored(Hash),
+        Claimed(ProgramId, AccountId, Balance),
+        /// The rewards of the account were claimed and paid out to the beneficiary
+        ClaimedTo(ProgramId, AccountId, AccountId, Balance),
+        /// A batch of claims was interrupted by the failure of the claim at the index
+        BatchInterrupted(u32, DispatchError),
+        /// The root hash was stored, rewards can be claimed against it until the expiry block if any
+        RootHashStored(ProgramId, Hash, Option<BlockNumber>),
+        /// The root hash was revoked, rewards can no longer be claimed against it
+        RootHashRevoked(ProgramId, Hash),
+        /// The program was created or updated
+        ProgramSet(ProgramId),
+        /// The balance of the funding account of the program can't cover the committed rewards
+        /// that were not claimed yet
+        Underfunded(ProgramId, Balance, Balance),
     }
 }
 
@@ -79,7 +230,11 @@ decl_module! {
 
         fn deposit_event() = default;
 
-        /// Claims CFG tokens awarded through tinlake investments
+        fn on_runtime_upgrade() -> Weight {
+            Self::migrate_legacy_program()
+        }
+
+        /// Claims CFG tokens awarded by a program, e.g. through tinlake investments
         /// Feeless dispatchable function
         /// The extrinsic is validated by the custom `validate_unsigned` function below
         ///
@@ -87,73 +242,148 @@ decl_module! {
         /// - Based on hashes length
         /// # </weight>
         #[weight = (sorted_hashes.len().saturating_mul(1_000_000) as u64
-                    + T::DbWeight::get().reads_writes(2,2)
+                    + T::DbWeight::get().reads_writes(4,4)
                     + 195_000_000,
             DispatchClass::Normal, Pays::Yes)]
         pub fn claim(origin,
+                     program_id: ProgramId,
                      account_id: T::AccountId,
                      amount: T::Balance,
                      sorted_hashes: Vec<T::Hash>,
         ) -> DispatchResult {
             ensure_none(origin)?;
 
-            ensure!(Self::verify_proofs(&account_id, &amount, &sorted_hashes), Error::<T>::InvalidProofs);
+            Self::do_claim(program_id, &account_id, amount, &sorted_hashes, &account_id)?;
+            Self::deposit_event(RawEvent::Claimed(program_id, account_id, amount));
 
-            let claimed = Self::get_account_balance(&account_id);
+            Ok(())
+        }
 
-            // Payout = amount - claim
-            let payout = amount.checked_sub(&claimed)
-                .ok_or(Error::<T>::InsufficientBalance)?;
+        /// Claims the rewards of a leaf and pays them out to the beneficiary that the account of
+        /// the leaf authorized by signing the [Module::claim_to_payload]
+        /// Can be submitted by any account, e.g. a custodian
+        ///
+        /// # <weight>
+        /// - Based on hashes length and signature verification
+        /// # </weight>
+        #[weight = claim_to_weight::<T>(claim)]
+        pub fn claim_to(origin, program_id: ProgramId, claim: ClaimToOf<T>) -> DispatchResult {
+            ensure_signed(origin)?;
 
-            // Payout must not be less than minimum allowed
-            ensure!(payout >= MIN_PAYOUT.saturated_into(),
-                    Error::<T>::UnderMinPayout);
+            Self::do_claim_to(program_id, claim)
+        }
 
-            let source = MODULE_ID.into_account();
+        /// Claims the rewards of many leaves like [Module::claim_to]
+        /// The claims are processed in order until one fails
+        ///
+        /// # <weight>
+        /// - Sum of the weights of the claims
+        /// # </weight>
+        #[weight = claims.iter().fold(0 as Weight, |weight, claim| weight.saturating_add(claim_to_weight::<T>(claim)))]
+        pub fn claim_batch(origin, program_id: ProgramId, claims: Vec<ClaimToOf<T>>) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            for (index, claim) in claims.into_iter().enumerate() {
+                if let Err(e) = Self::do_claim_to(program_id, claim) {
+                    Self::deposit_event(RawEvent::BatchInterrupted(index as u32, e));
+                    return Ok(());
+                }
+            }
 
-            // Transfer payout amount
-            <pallet_balances::Module<T> as Currency<_>>::transfer(
-                &source,
-                &account_id,
-                payout,
-                KeepAlive,
-            )?;
+            Ok(())
+        }
 
-            // Set account balance to amount
-            AccountBalances::<T>::insert(account_id.clone(), amount);
+        /// Admin function that creates or updates a program
+        /// Controlled by custom origin or root
+        ///
+        /// # <weight>
+        /// - Based on origin check and write op
+        /// # </weight>
+        #[weight = 190_000_000]
+        pub fn set_program(origin, program_id: ProgramId, program: ProgramOf<T>) -> DispatchResult {
+            Self::can_update_upload_account(origin)?;
 
-            Self::deposit_event(RawEvent::Claimed(account_id, amount));
+            <Programs<T>>::insert(program_id, program);
+            Self::deposit_event(RawEvent::ProgramSet(program_id));
 
             Ok(())
         }
 
-        /// Admin function that sets the allowed upload account to add root hashes
+        /// Admin function that sets the allowed upload account to add root hashes of a program
         /// Controlled by custom origin or root
         /// 
         /// # <weight>
         /// - Based on origin check and write op
         /// # </weight>
         #[weight = 190_000_000]
-        pub fn set_upload_account(origin, account_id: T::AccountId) -> DispatchResult {
+        pub fn set_upload_account(origin, program_id: ProgramId, account_id: T::AccountId) -> DispatchResult {
             Self::can_update_upload_account(origin)?;
 
-            <UploadAccount<T>>::put(account_id);
+            <Programs<T>>::try_mutate(program_id, |program| -> DispatchResult {
+                let program = program.as_mut().ok_or(Error::<T>::UnknownProgram)?;
+                program.uploader = account_id;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::ProgramSet(program_id));
 
             Ok(())
         }
 
-        /// Stores root hash for correspondent claim merkle tree run
+        /// Stores root hash for correspondent claim merkle tree run of a program, optionally
+        /// with the last block at which rewards can be claimed against it and the vesting of
+        /// the rewards. A root hash is stored once, its terms can't be changed afterwards.
         ///
         /// # <weight>
         /// - Based on origin check and write op
         /// # </weight>
-        #[weight = 185_000_000]
-        pub fn store_root_hash(origin, root_hash: T::Hash) -> DispatchResult {
+        #[weight = 185_000_000 + T::DbWeight::get().reads(4)]
+        pub fn store_root_hash(origin,
+                               program_id: ProgramId,
+                               root_hash: T::Hash,
+                               committed: T::Balance,
+                               expiry: Option<T::BlockNumber>,
+                               vesting: Option<ClaimVesting<T::BlockNumber>>,
+        ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            ensure!(Self::get_upload_account() == who, Error::<T>::MustBeAdmin);
-            <RootHashes<T>>::insert(root_hash, true);
-            Self::deposit_event(RawEvent::RootHashStored(root_hash));
+            let program = Self::program(program_id).ok_or(Error::<T>::UnknownProgram)?;
+            ensure!(program.uploader == who, Error::<T>::MustBeAdmin);
+            ensure!(!<RootHashes<T>>::contains_key(program_id, root_hash), Error::<T>::RootHashExists);
+            ensure!(!Self::is_revoked(program_id, root_hash), Error::<T>::RootHashWasRevoked);
+            <RootHashes<T>>::insert(program_id, root_hash, RootHashInfo {
+                uploaded_at: <frame_system::Module<T>>::block_number(),
+                committed,
+                expiry,
+                vesting,
+            });
+            Self::deposit_event(RawEvent::RootHashStored(program_id, root_hash, expiry));
+
+            // Amounts are cumulative, the committed rewards of the root hash include the ones
+            // that were already claimed
+            let balance = Self::funding_balance(program_id);
+            let liability = committed.saturating_sub(Self::total_claimed(program_id));
+            if balance < liability {
+                Self::deposit_event(RawEvent::Underfunded(program_id, balance, liability));
+            }
+
+            Ok(())
+        }
+
+        /// Admin function that revokes a root hash of a program, e.g. of a leaked or wrong
+        /// merkle tree. The root hash is kept as revoked so that it can't be stored again.
+        /// Controlled by custom origin or root
+        ///
+        /// # <weight>
+        /// - Based on origin check and write op
+        /// # </weight>
+        #[weight = 190_000_000]
+        pub fn revoke_root_hash(origin, program_id: ProgramId, root_hash: T::Hash) -> DispatchResult {
+            Self::can_update_upload_account(origin)?;
+
+            ensure!(<RootHashes<T>>::contains_key(program_id, root_hash), Error::<T>::UnknownRootHash);
+            <RootHashes<T>>::remove(program_id, root_hash);
+            <RevokedRootHashes<T>>::insert(program_id, root_hash, true);
+            Self::deposit_event(RawEvent::RootHashRevoked(program_id, root_hash));
 
             Ok(())
         }
@@ -176,6 +406,26 @@ impl<T: Trait> Module<T> {
         T::Hashing::hash(&h).into()
     }
 
+    /// Payload the account of a leaf signs to authorize the payout of its rewards to a beneficiary
+    pub fn claim_to_payload(
+        program_id: ProgramId,
+        account_id: &T::AccountId,
+        amount: &T::Balance,
+        beneficiary: &T::AccountId,
+    ) -> Vec<u8> {
+        (CLAIM_TO_PREFIX, program_id, account_id, amount, beneficiary).encode()
+    }
+
+    /// Account the rewards of the program are paid from. The legacy program keeps the account of
+    /// the module.
+    pub fn funding_account(program_id: ProgramId) -> T::AccountId {
+        if program_id == LEGACY_PROGRAM {
+            MODULE_ID.into_account()
+        } else {
+            MODULE_ID.into_sub_account(program_id)
+        }
+    }
+
     /// Returns true if the given origin can update the upload account
     fn can_update_upload_account(origin: T::Origin) -> DispatchResult {
         T::AdminOrigin::try_origin(origin)
@@ -185,11 +435,112 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
-    fn verify_proofs(account_id: &T::AccountId, amount: &T::Balance, sorted_hashes: &Vec<T::Hash>) -> bool {
+    /// Returns the program if its rewards can still be claimed
+    fn active_program(program_id: ProgramId) -> Result<ProgramOf<T>, Error<T>> {
+        let program = Self::program(program_id).ok_or(Error::<T>::UnknownProgram)?;
+        let now = <frame_system::Module<T>>::block_number();
+        ensure!(program.expiry.map_or(true, |expiry| now <= expiry), Error::<T>::ProgramExpired);
+        Ok(program)
+    }
+
+    /// Checks the claim of the rewards of the account of the leaf that were not claimed yet by the
+    /// beneficiary. Returns the payout and its vested part, along with the vesting of the root
+    /// hash, if any.
+    fn check_claim(
+        program_id: ProgramId,
+        account_id: &T::AccountId,
+        amount: &T::Balance,
+        sorted_hashes: &Vec<T::Hash>,
+        beneficiary: &T::AccountId,
+    ) -> Result<(T::Balance, Option<(T::Balance, ClaimVesting<T::BlockNumber>)>), Error<T>> {
+        let program = Self::active_program(program_id)?;
+        let root = Self::proven_root_hash(program_id, account_id, amount, sorted_hashes)
+            .ok_or(Error::<T>::InvalidProofs)?;
+
+        let claimed = Self::get_account_balance(program_id, account_id);
+
+        // Payout = amount - claim
+        let payout = amount.checked_sub(&claimed)
+            .ok_or(Error::<T>::InsufficientBalance)?;
+
+        // Payout must not be less than minimum allowed
+        ensure!(payout >= program.min_payout,
+                Error::<T>::UnderMinPayout);
+
+        // Vested part of the payout, checked like a vested transfer before anything is paid out
+        let vesting = root.vesting
+            .map(|vesting| (vesting.vested.mul_floor(payout), vesting))
+            .filter(|(locked, _)| !locked.is_zero());
+        if let Some((locked, _)) = vesting {
+            ensure!(locked >= T::MinVestedTransfer::get(), Error::<T>::VestedAmountLow);
+            ensure!(T::Vesting::vesting_balance(beneficiary).is_none(), Error::<T>::ExistingVestingSchedule);
+        }
+
+        Ok((payout, vesting))
+    }
+
+    /// Pays out the rewards of the account of the leaf that were not claimed yet to the beneficiary.
+    /// Replays are prevented by the cumulative amount claimed by the account.
+    fn do_claim(
+        program_id: ProgramId,
+        account_id: &T::AccountId,
+        amount: T::Balance,
+        sorted_hashes: &Vec<T::Hash>,
+        beneficiary: &T::AccountId,
+    ) -> DispatchResult {
+        let (payout, vesting) = Self::check_claim(program_id, account_id, &amount, sorted_hashes, beneficiary)?;
+
+        let source = Self::funding_account(program_id);
+
+        // Transfer payout amount
+        <pallet_balances::Module<T> as Currency<_>>::transfer(
+            &source,
+            beneficiary,
+            payout,
+            KeepAlive,
+        )?;
+
+        // Lock the vested part
+        if let Some((locked, vesting)) = vesting {
+            let per_block = vesting.per_block.mul_floor(locked).max(1u32.into());
+            let starting_block = <frame_system::Module<T>>::block_number().saturating_add(vesting.cliff);
+            T::Vesting::add_vesting_schedule(beneficiary, locked, per_block, starting_block)?;
+        }
+
+        // Set account balance to amount
+        AccountBalances::<T>::insert(program_id, account_id, amount);
+        TotalClaimed::<T>::mutate(program_id, |total| *total = total.saturating_add(payout));
+
+        Ok(())
+    }
+
+    fn do_claim_to(program_id: ProgramId, claim: ClaimToOf<T>) -> DispatchResult {
+        let ClaimTo { account_id, amount, sorted_hashes, beneficiary, signature } = claim;
+
+        let payload = Self::claim_to_payload(program_id, &account_id, &amount, &beneficiary);
+        ensure!(signature.verify(&payload[..], &account_id), Error::<T>::InvalidSignature);
+
+        Self::do_claim(program_id, &account_id, amount, &sorted_hashes, &beneficiary)?;
+        Self::deposit_event(RawEvent::ClaimedTo(program_id, account_id, beneficiary, amount));
+
+        Ok(())
+    }
+
+    fn verify_proofs(program_id: ProgramId, account_id: &T::AccountId, amount: &T::Balance, sorted_hashes: &Vec<T::Hash>) -> bool {
+        Self::proven_root_hash(program_id, account_id, amount, sorted_hashes).is_some()
+    }
+
+    /// Returns the active root hash of the program the proofs of the leaf lead to
+    fn proven_root_hash(
+        program_id: ProgramId,
+        account_id: &T::AccountId,
+        amount: &T::Balance,
+        sorted_hashes: &Vec<T::Hash>,
+    ) -> Option<RootHashInfoOf<T>> {
         // Number of proofs should practically never be >30. Checking this
         // blocks abuse.
         if sorted_hashes.len() > 30 {
-            return false;
+            return None;
         }
 
         // Concat account id : amount
@@ -207,10 +558,97 @@ impl<T: Trait> Module<T> {
             root_hash = leaf_hash;
         }
 
-        Self::get_root_hash(root_hash)
+        let now = <frame_system::Module<T>>::block_number();
+        Self::get_root_hash(program_id, root_hash).filter(|info| info.is_active(&now))
+    }
+
+    /// Returns the root hashes of the program that rewards can still be claimed against
+    pub fn active_root_hashes(program_id: ProgramId) -> Vec<(T::Hash, RootHashInfoOf<T>)> {
+        let now = <frame_system::Module<T>>::block_number();
+        <RootHashes<T>>::iter_prefix(program_id)
+            .filter(|(_, info)| info.is_active(&now))
+            .collect()
+    }
+
+    /// Returns the balance of the funding account of the program against the committed rewards
+    /// of its active root hashes that were not claimed yet. Amounts are cumulative, so the
+    /// liability is the largest committed amount less the rewards that were already claimed.
+    pub fn funding(program_id: ProgramId) -> ProgramFunding<T::Balance> {
+        let committed = Self::active_root_hashes(program_id)
+            .into_iter()
+            .map(|(_, info)| info.committed)
+            .max()
+            .unwrap_or_else(Zero::zero);
+
+        ProgramFunding {
+            balance: Self::funding_balance(program_id),
+            liability: committed.saturating_sub(Self::total_claimed(program_id)),
+        }
+    }
+
+    fn funding_balance(program_id: ProgramId) -> T::Balance {
+        <pallet_balances::Module<T> as Currency<_>>::free_balance(&Self::funding_account(program_id))
+    }
+
+    /// Moves the upload account, root hashes and claimed balances of the single program that
+    /// existed before programs were introduced into the legacy program.
+    fn migrate_legacy_program() -> Weight {
+        if <Programs<T>>::contains_key(LEGACY_PROGRAM) {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let uploader = migration::take_storage_value::<T::AccountId>(b"RadClaims", b"UploadAccount", &[])
+            .unwrap_or_default();
+        <Programs<T>>::insert(LEGACY_PROGRAM, Program {
+            uploader,
+            min_payout: MIN_PAYOUT.saturated_into(),
+            expiry: None,
+        });
+
+        // Keys of the legacy maps are blake2_128_concat hashed, i.e. the key follows 16 bytes
+        let root_hashes: Vec<_> = migration::StorageIterator::<bool>::new(b"RadClaims", b"RootHashes")
+            .drain()
+            .collect();
+        let balances: Vec<_> = migration::StorageIterator::<T::Balance>::new(b"RadClaims", b"AccountBalances")
+            .drain()
+            .collect();
+        let migrated = (root_hashes.len() + balances.len()) as Weight;
+
+        // The upload block and committed amount of legacy root hashes are not known, the block of
+        // the migration is used and nothing is committed
+        let uploaded_at = <frame_system::Module<T>>::block_number();
+        for (key, stored) in root_hashes {
+            if let Some(root_hash) = key.get(16..).and_then(|mut key| T::Hash::decode(&mut key).ok()) {
+                if stored {
+                    <RootHashes<T>>::insert(LEGACY_PROGRAM, root_hash, RootHashInfo {
+                        uploaded_at,
+                        committed: Zero::zero(),
+                        expiry: None,
+                        vesting: None,
+                    });
+                }
+            }
+        }
+        let mut total_claimed: T::Balance = Zero::zero();
+        for (key, balance) in balances {
+            if let Some(account_id) = key.get(16..).and_then(|mut key| T::AccountId::decode(&mut key).ok()) {
+                AccountBalances::<T>::insert(LEGACY_PROGRAM, account_id, balance);
+                total_claimed = total_claimed.saturating_add(balance);
+            }
+        }
+        TotalClaimed::<T>::insert(LEGACY_PROGRAM, total_claimed);
+
+        T::DbWeight::get().reads_writes(migrated + 2, migrated.saturating_mul(2) + 3)
     }
 }
 
+/// Weight of a claim to a beneficiary, based on hashes length and signature verification
+fn claim_to_weight<T: Trait>(claim: &ClaimToOf<T>) -> Weight {
+    (claim.sorted_hashes.len().saturating_mul(1_000_000) as Weight)
+        .saturating_add(T::DbWeight::get().reads_writes(4, 5))
+        .saturating_add(245_000_000)
+}
+
 impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
     type Call = Call<T>;
 
@@ -218,19 +656,22 @@ impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
         _source: TransactionSource,
         call: &Self::Call,
     ) -> TransactionValidity {
-        if let Call::claim(account_id, amount, sorted_hashes) = call {
-            // Check that proofs are valid with a root that exists in the root hash storage
-            if Self::verify_proofs(account_id, amount, sorted_hashes.into()) {
-                return ValidTransaction::with_tag_prefix("RadClaims")
+        if let Call::claim(program_id, account_id, amount, sorted_hashes) = call {
+            // Check that proofs are valid with a root that exists in the root hash storage of an
+            // active program, and that the payout can be made
+            return match Self::check_claim(*program_id, account_id, amount, sorted_hashes, account_id) {
+                Ok(_) => ValidTransaction::with_tag_prefix("RadClaims")
                     .priority(T::UnsignedPriority::get())
-                    .and_provides((account_id, amount, sorted_hashes))
+                    .and_provides((program_id, account_id, amount, sorted_hashes))
                     .longevity(TryInto::<u64>::try_into(
                         T::Longevity::get())
                         .unwrap_or(64_u64))
                     .propagate(true)
-                    .build()
-            } else {
-                return InvalidTransaction::BadProof.into();
+                    .build(),
+                Err(Error::<T>::UnknownProgram)
+                | Err(Error::<T>::ProgramExpired)
+                | Err(Error::<T>::InvalidProofs) => InvalidTransaction::BadProof.into(),
+                Err(error) => InvalidTransaction::Custom(error.as_u8()).into(),
             }
         }
 
@@ -250,8 +691,8 @@ mod tests {
     use sp_core::H256;
     use sp_runtime::Perbill;
     use sp_runtime::{
-        testing::Header,
-        traits::{BadOrigin, BlakeTwo256, Hash, IdentityLookup},
+        testing::{Header, TestSignature, UintAuthorityId},
+        traits::{BadOrigin, BlakeTwo256, ConvertInto, Hash, IdentityLookup},
     };
     pub use pallet_balances as balances;
     use pallet_balances::Error as BalancesError;
@@ -310,6 +751,21 @@ mod tests {
         type UnsignedPriority = UnsignedPriority;
         type AdminOrigin = EnsureSignedBy<One, u64>;
         type Currency = Balances;
+        type Signature = TestSignature;
+        type Signer = UintAuthorityId;
+        type Vesting = Vesting;
+        type MinVestedTransfer = MinVestedTransfer;
+    }
+
+    parameter_types! {
+        pub const MinVestedTransfer: u128 = 10 * currency::CFG;
+    }
+    impl pallet_vesting::Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type BlockNumberToBalance = ConvertInto;
+        type MinVestedTransfer = MinVestedTransfer;
+        type WeightInfo = ();
     }
 
     parameter_types! {
@@ -327,6 +783,7 @@ mod tests {
     type RadClaims = Module<Test>;
     type System = frame_system::Module<Test>;
     type Balances = pallet_balances::Module<Test>;
+    type Vesting = pallet_vesting::Module<Test>;
 
     pub const ADMIN: u64 = 0x1;
     pub const USER_A: u64 = 0x2;
@@ -348,7 +805,16 @@ mod tests {
         }
             .assimilate_storage(&mut t)
             .unwrap();
-        t.into()
+
+        let mut ext: sp_io::TestExternalities = t.into();
+        ext.execute_with(|| {
+            <Programs<Test>>::insert(LEGACY_PROGRAM, Program {
+                uploader: 0x0,
+                min_payout: MIN_PAYOUT,
+                expiry: None,
+            });
+        });
+        ext
     }
 
     #[test]
@@ -373,25 +839,25 @@ mod tests {
             ];
 
             // Abuse DDoS attach check
-            assert_eq!(RadClaims::verify_proofs(&USER_B, &amount, &sorted_hashes_long.to_vec()), false);
+            assert_eq!(RadClaims::verify_proofs(LEGACY_PROGRAM, &USER_B, &amount, &sorted_hashes_long.to_vec()), false);
 
             // Wrong sorted hashes for merkle tree
             let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
-            assert_eq!(RadClaims::verify_proofs(&USER_B, &amount, &one_sorted_hashes.to_vec()), false);
+            assert_eq!(RadClaims::verify_proofs(LEGACY_PROGRAM, &USER_B, &amount, &one_sorted_hashes.to_vec()), false);
 
             let mut v: Vec<u8> = USER_B.encode();
             v.extend(amount.encode());
 
             // Single-leaf tree
-            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), ADMIN));
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
             let leaf_hash = <Test as frame_system::Trait>::Hashing::hash(&v);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), leaf_hash));
-            assert_eq!(RadClaims::verify_proofs(&USER_B, &amount, &[].to_vec()), true);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, leaf_hash, 0, None, None));
+            assert_eq!(RadClaims::verify_proofs(LEGACY_PROGRAM, &USER_B, &amount, &[].to_vec()), true);
 
             // Two-leaf tree
             let root_hash = RadClaims::sorted_hash_of(&leaf_hash, &one_sorted_hashes[0]);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), root_hash));
-            assert_eq!(RadClaims::verify_proofs(&USER_B, &amount, &one_sorted_hashes.to_vec()), true);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None));
+            assert_eq!(RadClaims::verify_proofs(LEGACY_PROGRAM, &USER_B, &amount, &one_sorted_hashes.to_vec()), true);
 
             // 10-leaf tree
             let leaf_hash_0: H256 = [0; 32].into();
@@ -415,36 +881,41 @@ mod tests {
             let node_root = RadClaims::sorted_hash_of(&node_000, &node_4);
 
             let four_sorted_hashes: [H256; 4] = [leaf_hash_3.into(), node_0.into(), node_01.into(), node_4.into()];
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), node_root));
-            assert_eq!(RadClaims::verify_proofs(&USER_B, &amount, &four_sorted_hashes.to_vec()), true);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, node_root, 0, None, None));
+            assert_eq!(RadClaims::verify_proofs(LEGACY_PROGRAM, &USER_B, &amount, &four_sorted_hashes.to_vec()), true);
         });
     }
 
     #[test]
     fn set_upload_account() {
         new_test_ext().execute_with(|| {
-            assert_eq!(RadClaims::get_upload_account(), 0x0);
-            assert_err!(RadClaims::set_upload_account(Origin::signed(USER_A), USER_A), BadOrigin);
-            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), USER_A));
-            assert_eq!(RadClaims::get_upload_account(), USER_A);
+            assert_eq!(RadClaims::program(LEGACY_PROGRAM).unwrap().uploader, 0x0);
+            assert_err!(RadClaims::set_upload_account(Origin::signed(USER_A), LEGACY_PROGRAM, USER_A), BadOrigin);
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, USER_A));
+            assert_eq!(RadClaims::program(LEGACY_PROGRAM).unwrap().uploader, USER_A);
         });
     }
 
     #[test]
     fn store_root_hash() {
         new_test_ext().execute_with(|| {
-            assert_eq!(RadClaims::get_upload_account(), 0x0);
+            assert_eq!(RadClaims::program(LEGACY_PROGRAM).unwrap().uploader, 0x0);
             // USER_A not allowed to upload hash
             let root_hash = <Test as frame_system::Trait>::Hashing::hash(&[0; 32]);
             assert_err!(
-                RadClaims::store_root_hash(Origin::signed(USER_A), root_hash),
+                RadClaims::store_root_hash(Origin::signed(USER_A), LEGACY_PROGRAM, root_hash, 0, None, None),
                 Error::<Test>::MustBeAdmin
             );
             // Adding ADMIN as allowed upload account
-            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), ADMIN));
-            assert_eq!(RadClaims::get_upload_account(), ADMIN);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), root_hash));
-            assert_eq!(RadClaims::get_root_hash(root_hash), true);
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+            assert_eq!(RadClaims::program(LEGACY_PROGRAM).unwrap().uploader, ADMIN);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None));
+            assert!(RadClaims::get_root_hash(LEGACY_PROGRAM, root_hash).is_some());
+            // The terms of a stored root hash can't be changed
+            assert_err!(
+                RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, Some(10), None),
+                Error::<Test>::RootHashExists
+            );
         });
     }
 
@@ -469,47 +940,47 @@ mod tests {
 
             // Bad origin, signed vs unsigned
             assert_err!(
-                RadClaims::claim(Origin::signed(USER_B), USER_B, amount, one_sorted_hashes.to_vec()),
+                RadClaims::claim(Origin::signed(USER_B), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()),
                 BadOrigin
             );
 
             // proof validation error - roothash not stored
             assert_err!(
-                RadClaims::claim(Origin::none(), USER_B, amount, one_sorted_hashes.to_vec()),
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()),
                 Error::<Test>::InvalidProofs
             );
 
             // Set valid proofs
-            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), ADMIN));
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
 
             let short_root_hash = pre_calculate_single_root(
                 &USER_B, &(4 * currency::CFG), &one_sorted_hashes[0]);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), short_root_hash));
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, short_root_hash, 0, None, None));
 
             // Minimum payout not met
             assert_err!(
-                RadClaims::claim(Origin::none(), USER_B, 4 * currency::CFG, one_sorted_hashes.to_vec()),
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, 4 * currency::CFG, one_sorted_hashes.to_vec()),
                 Error::<Test>::UnderMinPayout
             );
 
             let long_root_hash = pre_calculate_single_root(
                 &USER_B, &(10001 * currency::CFG), &one_sorted_hashes[0]);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), long_root_hash));
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, long_root_hash, 0, None, None));
 
             // Claims Module Account does not have enough balance
             assert_err!(
-                RadClaims::claim(Origin::none(), USER_B, 10001 * currency::CFG, one_sorted_hashes.to_vec()),
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, 10001 * currency::CFG, one_sorted_hashes.to_vec()),
                 BalancesError::<Test, _>::InsufficientBalance
             );
 
             // Ok
             let ok_root_hash = pre_calculate_single_root(
                 &USER_B, &amount, &one_sorted_hashes[0]);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), ok_root_hash));
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, ok_root_hash, 0, None, None));
 
             let account_balance = <pallet_balances::Module<Test>>::free_balance(USER_B);
-            assert_ok!(RadClaims::claim(Origin::none(), USER_B, amount, one_sorted_hashes.to_vec()));
-            assert_eq!(RadClaims::get_account_balance(USER_B), amount);
+            assert_ok!(RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()));
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_B), amount);
             let account_new_balance = <pallet_balances::Module<Test>>::free_balance(USER_B);
             assert_eq!(account_new_balance, account_balance + amount);
 
@@ -517,9 +988,9 @@ mod tests {
             // Since balance logic is accumulative
             let past_root_hash = pre_calculate_single_root(
                 &USER_B, &(50 * currency::CFG), &one_sorted_hashes[0]);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), past_root_hash));
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, past_root_hash, 0, None, None));
             assert_err!(
-                RadClaims::claim(Origin::none(), USER_B, 50 * currency::CFG, one_sorted_hashes.to_vec()),
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, 50 * currency::CFG, one_sorted_hashes.to_vec()),
                 Error::<Test>::InsufficientBalance
             );
 
@@ -540,19 +1011,506 @@ mod tests {
             ];
 
             // Abuse DDoS attach check
-            let inner_long = Call::claim(USER_B, amount, sorted_hashes_long.to_vec());
+            let inner_long = Call::claim(LEGACY_PROGRAM, USER_B, amount, sorted_hashes_long.to_vec());
             assert_err!(
                 <RadClaims as sp_runtime::traits::ValidateUnsigned>::pre_dispatch(&inner_long),
                 InvalidTransaction::BadProof
             );
 
             // Two-leaf tree success
-            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), ADMIN));
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
             let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
             let root_hash = pre_calculate_single_root(&USER_B, &amount, &one_sorted_hashes[0]);
-            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), root_hash));
-            let inner = Call::claim(USER_B, amount, one_sorted_hashes.to_vec());
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None));
+            let inner = Call::claim(LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec());
             assert_ok!(<RadClaims as sp_runtime::traits::ValidateUnsigned>::pre_dispatch(&inner));
+
+            // Vested part of the payout is checked like a vested transfer
+            let low_amount: u128 = 10 * currency::CFG;
+            let vesting = ClaimVesting { vested: Perbill::from_percent(80), cliff: 10, per_block: Perbill::from_percent(1) };
+            let root_hash = pre_calculate_single_root(&USER_A, &low_amount, &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, Some(vesting)));
+            let inner = Call::claim(LEGACY_PROGRAM, USER_A, low_amount, one_sorted_hashes.to_vec());
+            assert_err!(
+                <RadClaims as sp_runtime::traits::ValidateUnsigned>::pre_dispatch(&inner),
+                InvalidTransaction::Custom(Error::<Test>::VestedAmountLow.as_u8())
+            );
+        });
+    }
+
+    #[test]
+    fn programs() {
+        new_test_ext().execute_with(|| {
+            let program_id: ProgramId = 1;
+            let amount: u128 = 100 * currency::CFG;
+            let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
+            let root_hash = pre_calculate_single_root(&USER_B, &amount, &one_sorted_hashes[0]);
+
+            // Unknown program
+            assert_err!(
+                RadClaims::set_upload_account(Origin::signed(ADMIN), program_id, ADMIN),
+                Error::<Test>::UnknownProgram
+            );
+            assert_err!(
+                RadClaims::claim(Origin::none(), program_id, USER_B, amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::UnknownProgram
+            );
+
+            // Only admin sets programs
+            let program = Program { uploader: USER_A, min_payout: amount, expiry: Some(10) };
+            assert_err!(
+                RadClaims::set_program(Origin::signed(USER_A), program_id, program.clone()),
+                BadOrigin
+            );
+            assert_ok!(RadClaims::set_program(Origin::signed(ADMIN), program_id, program.clone()));
+            assert_eq!(RadClaims::program(program_id), Some(program));
+
+            // Root hashes are stored per program
+            assert_err!(
+                RadClaims::store_root_hash(Origin::signed(ADMIN), program_id, root_hash, 0, None, None),
+                Error::<Test>::MustBeAdmin
+            );
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(USER_A), program_id, root_hash, 0, None, None));
+            assert!(RadClaims::get_root_hash(program_id, root_hash).is_some());
+            assert!(RadClaims::get_root_hash(LEGACY_PROGRAM, root_hash).is_none());
+            assert_err!(
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::InvalidProofs
+            );
+
+            // Program has its own funding account
+            let funding_account = RadClaims::funding_account(program_id);
+            assert_ne!(funding_account, RadClaims::funding_account(LEGACY_PROGRAM));
+            assert_err!(
+                RadClaims::claim(Origin::none(), program_id, USER_B, amount, one_sorted_hashes.to_vec()),
+                BalancesError::<Test, _>::InsufficientBalance
+            );
+            let _ = Balances::deposit_creating(&funding_account, ENDOWED_BALANCE);
+            let legacy_balance = Balances::free_balance(RadClaims::funding_account(LEGACY_PROGRAM));
+
+            // Program can no longer be claimed after its expiry
+            System::set_block_number(11);
+            assert_err!(
+                RadClaims::claim(Origin::none(), program_id, USER_B, amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::ProgramExpired
+            );
+            assert_err!(
+                <RadClaims as sp_runtime::traits::ValidateUnsigned>::pre_dispatch(
+                    &Call::claim(program_id, USER_B, amount, one_sorted_hashes.to_vec())),
+                InvalidTransaction::BadProof
+            );
+
+            System::set_block_number(10);
+            assert_ok!(RadClaims::claim(Origin::none(), program_id, USER_B, amount, one_sorted_hashes.to_vec()));
+            assert_eq!(RadClaims::get_account_balance(program_id, USER_B), amount);
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_B), 0);
+            assert_eq!(Balances::free_balance(USER_B), amount);
+            assert_eq!(Balances::free_balance(funding_account), ENDOWED_BALANCE - amount);
+            assert_eq!(Balances::free_balance(RadClaims::funding_account(LEGACY_PROGRAM)), legacy_balance);
+        });
+    }
+
+    #[test]
+    fn revoke_and_expire_root_hash() {
+        new_test_ext().execute_with(|| {
+            let amount: u128 = 100 * currency::CFG;
+            let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
+            let root_hash = pre_calculate_single_root(&USER_B, &amount, &one_sorted_hashes[0]);
+            let expiring_root_hash = pre_calculate_single_root(&USER_B, &(2 * amount), &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+
+            System::set_block_number(5);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None));
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, expiring_root_hash, 0, Some(10), None));
+            let mut active = RadClaims::active_root_hashes(LEGACY_PROGRAM);
+            active.sort_by_key(|(hash, _)| *hash);
+            let mut expected = vec![
+                (root_hash, RootHashInfo { uploaded_at: 5, committed: 0, expiry: None, vesting: None }),
+                (expiring_root_hash, RootHashInfo { uploaded_at: 5, committed: 0, expiry: Some(10), vesting: None }),
+            ];
+            expected.sort_by_key(|(hash, _)| *hash);
+            assert_eq!(active, expected);
+
+            // Only admin revokes root hashes
+            assert_err!(
+                RadClaims::revoke_root_hash(Origin::signed(USER_A), LEGACY_PROGRAM, root_hash),
+                BadOrigin
+            );
+            assert_ok!(RadClaims::revoke_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash));
+            assert!(RadClaims::get_root_hash(LEGACY_PROGRAM, root_hash).is_none());
+            assert_err!(
+                RadClaims::revoke_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash),
+                Error::<Test>::UnknownRootHash
+            );
+            // The uploader can't store a revoked root hash again
+            assert_err!(
+                RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None),
+                Error::<Test>::RootHashWasRevoked
+            );
+            assert_err!(
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::InvalidProofs
+            );
+
+            // Claims against an expired root hash fail
+            System::set_block_number(11);
+            assert_eq!(RadClaims::active_root_hashes(LEGACY_PROGRAM), vec![]);
+            assert_err!(
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, 2 * amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::InvalidProofs
+            );
+
+            System::set_block_number(10);
+            assert_ok!(RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, 2 * amount, one_sorted_hashes.to_vec()));
+        });
+    }
+
+    fn claim_to_of(
+        account_id: u64,
+        amount: u128,
+        sorted_hashes: &[H256],
+        beneficiary: u64,
+    ) -> ClaimToOf<Test> {
+        let payload = RadClaims::claim_to_payload(LEGACY_PROGRAM, &account_id, &amount, &beneficiary);
+        ClaimTo {
+            account_id,
+            amount,
+            sorted_hashes: sorted_hashes.to_vec(),
+            beneficiary,
+            signature: TestSignature(account_id, payload),
+        }
+    }
+
+    #[test]
+    fn claim_to() {
+        new_test_ext().execute_with(|| {
+            let amount: u128 = 100 * currency::CFG;
+            let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+            let root_hash = pre_calculate_single_root(&USER_B, &amount, &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None));
+
+            // Signed by another account than the one of the leaf
+            let mut claim = claim_to_of(USER_B, amount, &one_sorted_hashes, USER_A);
+            claim.signature = TestSignature(USER_A, claim.signature.1.clone());
+            assert_err!(
+                RadClaims::claim_to(Origin::signed(ADMIN), LEGACY_PROGRAM, claim),
+                Error::<Test>::InvalidSignature
+            );
+
+            // Beneficiary differs from the one that was authorized
+            let mut claim = claim_to_of(USER_B, amount, &one_sorted_hashes, USER_A);
+            claim.beneficiary = ADMIN;
+            assert_err!(
+                RadClaims::claim_to(Origin::signed(ADMIN), LEGACY_PROGRAM, claim),
+                Error::<Test>::InvalidSignature
+            );
+
+            // Must be signed
+            let claim = claim_to_of(USER_B, amount, &one_sorted_hashes, USER_A);
+            assert_err!(
+                RadClaims::claim_to(Origin::none(), LEGACY_PROGRAM, claim.clone()),
+                BadOrigin
+            );
+
+            // Payout goes to the beneficiary
+            assert_ok!(RadClaims::claim_to(Origin::signed(ADMIN), LEGACY_PROGRAM, claim.clone()));
+            assert_eq!(Balances::free_balance(USER_A), 1 + amount);
+            assert_eq!(Balances::free_balance(USER_B), 0);
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_B), amount);
+
+            // Replays are prevented by the claimed balance of the account of the leaf
+            assert_err!(
+                RadClaims::claim_to(Origin::signed(ADMIN), LEGACY_PROGRAM, claim),
+                Error::<Test>::UnderMinPayout
+            );
+            assert_err!(
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::UnderMinPayout
+            );
+        });
+    }
+
+    #[test]
+    fn claim_batch() {
+        new_test_ext().execute_with(|| {
+            let amount: u128 = 100 * currency::CFG;
+            // Two-leaf tree of the leaves of USER_A and USER_B
+            let leaf_hash = |account_id: u64| {
+                let mut v: Vec<u8> = account_id.encode();
+                v.extend(amount.encode());
+                <Test as frame_system::Trait>::Hashing::hash(&v)
+            };
+            let root_hash = RadClaims::sorted_hash_of(&leaf_hash(USER_A), &leaf_hash(USER_B));
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, None));
+
+            let claims = vec![
+                claim_to_of(USER_A, amount, &[leaf_hash(USER_B)], ADMIN),
+                claim_to_of(USER_B, amount, &[leaf_hash(USER_A)], ADMIN),
+            ];
+            let admin_balance = Balances::free_balance(ADMIN);
+            assert_ok!(RadClaims::claim_batch(Origin::signed(ADMIN), LEGACY_PROGRAM, claims.clone()));
+            assert_eq!(Balances::free_balance(ADMIN), admin_balance + 2 * amount);
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_A), amount);
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_B), amount);
+
+            // Replaying the batch is interrupted at its first claim
+            assert_ok!(RadClaims::claim_batch(Origin::signed(ADMIN), LEGACY_PROGRAM, claims));
+            assert_eq!(Balances::free_balance(ADMIN), admin_balance + 2 * amount);
+        });
+    }
+
+    #[test]
+    fn vested_claim() {
+        new_test_ext().execute_with(|| {
+            let amount: u128 = 100 * currency::CFG;
+            let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
+            let vesting = ClaimVesting { vested: Perbill::from_percent(80), cliff: 10, per_block: Perbill::from_percent(1) };
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+
+            // Vested part is less than the minimum of a vested transfer
+            let low_amount: u128 = 10 * currency::CFG;
+            let root_hash = pre_calculate_single_root(&USER_B, &low_amount, &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, Some(vesting.clone())));
+            assert_err!(
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, low_amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::VestedAmountLow
+            );
+
+            // Liquid part is free, vested part is locked until the cliff and unlocks per block
+            System::set_block_number(1);
+            let root_hash = pre_calculate_single_root(&USER_B, &amount, &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, Some(vesting.clone())));
+            assert_ok!(RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()));
+            assert_eq!(Balances::free_balance(USER_B), amount);
+            assert_eq!(Vesting::vesting(USER_B), Some(pallet_vesting::VestingInfo {
+                locked: 80 * currency::CFG,
+                per_block: 80 * currency::CFG / 100,
+                starting_block: 11,
+            }));
+            assert_eq!(Vesting::vesting_balance(&USER_B), Some(80 * currency::CFG));
+            System::set_block_number(21);
+            assert_eq!(Vesting::vesting_balance(&USER_B), Some(72 * currency::CFG));
+
+            // Vested part can't be paid out to a beneficiary that already has a vesting schedule,
+            // which is left as is
+            let root_hash = pre_calculate_single_root(&USER_B, &(2 * amount), &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 0, None, Some(vesting)));
+            let inner = Call::claim(LEGACY_PROGRAM, USER_B, 2 * amount, one_sorted_hashes.to_vec());
+            assert_err!(
+                <RadClaims as sp_runtime::traits::ValidateUnsigned>::pre_dispatch(&inner),
+                InvalidTransaction::Custom(Error::<Test>::ExistingVestingSchedule.as_u8())
+            );
+            assert_err!(
+                RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, 2 * amount, one_sorted_hashes.to_vec()),
+                Error::<Test>::ExistingVestingSchedule
+            );
+            assert_eq!(Balances::free_balance(USER_B), amount);
+            assert_eq!(Vesting::vesting_balance(&USER_B), Some(72 * currency::CFG));
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_B), amount);
+        });
+    }
+
+    #[test]
+    fn funding() {
+        new_test_ext().execute_with(|| {
+            let amount: u128 = 100 * currency::CFG;
+            let one_sorted_hashes: [H256; 1] = [[0; 32].into()];
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+            assert_eq!(RadClaims::funding(LEGACY_PROGRAM), ProgramFunding { balance: ENDOWED_BALANCE, liability: 0 });
+
+            let root_hash = pre_calculate_single_root(&USER_B, &amount, &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 3 * amount, None, None));
+            assert_eq!(RadClaims::funding(LEGACY_PROGRAM), ProgramFunding { balance: ENDOWED_BALANCE, liability: 3 * amount });
+
+            // Claims lower the balance and the liability alike
+            assert_ok!(RadClaims::claim(Origin::none(), LEGACY_PROGRAM, USER_B, amount, one_sorted_hashes.to_vec()));
+            assert_eq!(RadClaims::total_claimed(LEGACY_PROGRAM), amount);
+            assert_eq!(
+                RadClaims::funding(LEGACY_PROGRAM),
+                ProgramFunding { balance: ENDOWED_BALANCE - amount, liability: 2 * amount }
+            );
+
+            // Liability is the largest committed amount of the active root hashes
+            let root_hash = pre_calculate_single_root(&USER_B, &(2 * amount), &one_sorted_hashes[0]);
+            assert_ok!(RadClaims::store_root_hash(Origin::signed(ADMIN), LEGACY_PROGRAM, root_hash, 4 * amount, Some(10), None));
+            assert_eq!(
+                RadClaims::funding(LEGACY_PROGRAM),
+                ProgramFunding { balance: ENDOWED_BALANCE - amount, liability: 3 * amount }
+            );
+            System::set_block_number(11);
+            assert_eq!(
+                RadClaims::funding(LEGACY_PROGRAM),
+                ProgramFunding { balance: ENDOWED_BALANCE - amount, liability: 2 * amount }
+            );
+        });
+    }
+
+    /// Mock runtime with the accounts of the chain, which the tree builder encodes the claims of
+    mod chain_accounts {
+        use super::*;
+        use frame_system::EnsureRoot;
+        use sp_runtime::{AccountId32, MultiSignature};
+
+        mod vectors {
+            include!("../../tools/claims-tree/src/vectors.rs");
+        }
+
+        impl_outer_origin! {
+            pub enum Origin for Test where system = frame_system {}
+        }
+
+        #[derive(Clone, Eq, PartialEq)]
+        pub struct Test;
+        parameter_types! {
+            pub const BlockHashCount: u64 = 250;
+            pub const MaximumBlockWeight: Weight = 1024;
+            pub const MaximumBlockLength: u32 = 2 * 1024;
+            pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+            pub const Longevity: u32 = 64;
+            pub const UnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+            pub const MinVestedTransfer: u128 = 10 * currency::CFG;
+            pub const ExistentialDeposit: u128 = 1;
+        }
+        impl frame_system::Trait for Test {
+            type AccountId = AccountId32;
+            type Call = ();
+            type Lookup = IdentityLookup<Self::AccountId>;
+            type Index = u64;
+            type BlockNumber = u64;
+            type Hash = H256;
+            type Hashing = BlakeTwo256;
+            type Header = Header;
+            type Event = ();
+            type Origin = Origin;
+            type BlockHashCount = BlockHashCount;
+            type MaximumBlockWeight = MaximumBlockWeight;
+            type MaximumBlockLength = MaximumBlockLength;
+            type AvailableBlockRatio = AvailableBlockRatio;
+            type Version = ();
+            type ModuleToIndex = ();
+            type AccountData = balances::AccountData<u128>;
+            type OnNewAccount = ();
+            type OnKilledAccount = balances::Module<Test>;
+            type DbWeight = ();
+            type BlockExecutionWeight = ();
+            type ExtrinsicBaseWeight = ();
+            type MaximumExtrinsicWeight = ();
+            type BaseCallFilter = ();
+            type SystemWeightInfo = ();
+        }
+        impl Trait for Test {
+            type Event = ();
+            type Longevity = Longevity;
+            type UnsignedPriority = UnsignedPriority;
+            type AdminOrigin = EnsureRoot<AccountId32>;
+            type Currency = Balances;
+            type Signature = MultiSignature;
+            type Signer = <MultiSignature as Verify>::Signer;
+            type Vesting = Vesting;
+            type MinVestedTransfer = MinVestedTransfer;
+        }
+        impl pallet_vesting::Trait for Test {
+            type Event = ();
+            type Currency = Balances;
+            type BlockNumberToBalance = ConvertInto;
+            type MinVestedTransfer = MinVestedTransfer;
+            type WeightInfo = ();
+        }
+        impl pallet_balances::Trait for Test {
+            type Balance = u128;
+            type DustRemoval = ();
+            type Event = ();
+            type ExistentialDeposit = ExistentialDeposit;
+            type AccountStore = System;
+            type WeightInfo = ();
+        }
+
+        type RadClaims = Module<Test>;
+        type System = frame_system::Module<Test>;
+        type Balances = pallet_balances::Module<Test>;
+        type Vesting = pallet_vesting::Module<Test>;
+
+        fn admin() -> AccountId32 {
+            AccountId32::from([1; 32])
+        }
+
+        fn new_test_ext() -> sp_io::TestExternalities {
+            let mut t = frame_system::GenesisConfig::default()
+                .build_storage::<Test>()
+                .unwrap();
+
+            pallet_balances::GenesisConfig::<Test> {
+                balances: vec![(MODULE_ID.into_account(), ENDOWED_BALANCE)],
+            }
+                .assimilate_storage(&mut t)
+                .unwrap();
+
+            let mut ext: sp_io::TestExternalities = t.into();
+            ext.execute_with(|| {
+                // Vectors include claims below the minimum payout of the legacy program
+                <Programs<Test>>::insert(LEGACY_PROGRAM, Program {
+                    uploader: admin(),
+                    min_payout: 0,
+                    expiry: None,
+                });
+            });
+            ext
+        }
+
+        #[test]
+        fn verify_tree_builder_vectors() {
+            new_test_ext().execute_with(|| {
+                let root_hash: H256 = vectors::ROOT.into();
+                assert_ok!(RadClaims::store_root_hash(Origin::signed(admin()), LEGACY_PROGRAM, root_hash, 0, None, None));
+
+                for ((account, amount), proof) in vectors::CLAIMS.iter().zip(vectors::PROOFS) {
+                    let account_id = AccountId32::from(*account);
+                    let sorted_hashes: Vec<H256> = proof.iter().map(|hash| (*hash).into()).collect();
+                    assert!(RadClaims::verify_proofs(LEGACY_PROGRAM, &account_id, amount, &sorted_hashes));
+                    assert!(!RadClaims::verify_proofs(LEGACY_PROGRAM, &account_id, &(amount + 1), &sorted_hashes));
+
+                    assert_ok!(RadClaims::claim(Origin::none(), LEGACY_PROGRAM, account_id.clone(), *amount, sorted_hashes));
+                    assert_eq!(Balances::free_balance(&account_id), *amount);
+                    assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, &account_id), *amount);
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn migrate_legacy_program() {
+        new_test_ext().execute_with(|| {
+            let amount: u128 = 100 * currency::CFG;
+            let root_hash = <Test as frame_system::Trait>::Hashing::hash(&[0; 32]);
+            let blake2_128_concat = |key: &[u8]| -> Vec<u8> {
+                sp_io::hashing::blake2_128(key).iter().chain(key).cloned().collect()
+            };
+
+            // Storage layout before programs were introduced
+            <Programs<Test>>::remove(LEGACY_PROGRAM);
+            migration::put_storage_value(b"RadClaims", b"UploadAccount", &[], USER_A);
+            migration::put_storage_value(b"RadClaims", b"RootHashes", &blake2_128_concat(root_hash.as_ref()), true);
+            migration::put_storage_value(b"RadClaims", b"AccountBalances", &blake2_128_concat(&USER_B.encode()), amount);
+
+            RadClaims::migrate_legacy_program();
+
+            assert_eq!(RadClaims::program(LEGACY_PROGRAM), Some(Program {
+                uploader: USER_A,
+                min_payout: MIN_PAYOUT,
+                expiry: None,
+            }));
+            assert!(RadClaims::get_root_hash(LEGACY_PROGRAM, root_hash).is_some());
+            assert_eq!(RadClaims::get_account_balance(LEGACY_PROGRAM, USER_B), amount);
+            assert_eq!(RadClaims::total_claimed(LEGACY_PROGRAM), amount);
+            assert_eq!(migration::get_storage_value::<u64>(b"RadClaims", b"UploadAccount", &[]), None);
+
+            // Migration only runs once
+            assert_ok!(RadClaims::set_upload_account(Origin::signed(ADMIN), LEGACY_PROGRAM, ADMIN));
+            RadClaims::migrate_legacy_program();
+            assert_eq!(RadClaims::program(LEGACY_PROGRAM).unwrap().uploader, ADMIN);
+            assert!(RadClaims::get_root_hash(LEGACY_PROGRAM, root_hash).is_some());
         });
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,6 @@
 use sp_core::H256;
 use crate::va_registry::{Module, Trait};
-use crate::{nft, fees, va_registry};
+use crate::{nft, fees::{self, FeeKey}, va_registry};
 use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
 use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
@@ -59,9 +59,13 @@ impl frame_system::Trait for Test {
     type SystemWeightInfo = ();
 }
 
+parameter_types! {
+    pub const MaxMetadataLength: u32 = 1024;
+}
 impl nft::Trait for Test {
     type Event = MetaEvent;
     type AssetInfo = crate::va_registry::types::AssetInfo;
+    type MaxMetadataLength = MaxMetadataLength;
 }
 
 impl crate::anchor::Trait for Test {}
@@ -76,6 +80,13 @@ impl pallet_timestamp::Trait for Test {
 impl fees::Trait for Test {
     type Event = MetaEvent;
     type FeeChangeOrigin = frame_system::EnsureRoot<u64>;
+    type Author = ();
+    type TreasuryAccount = ();
+    type StakingPotAccount = ();
+    type CreditAccount = ();
+    type SpendOrigin = frame_system::EnsureRoot<u64>;
+    type MaxFeeHistory = ();
+    type CfgPrice = ();
 }
 
 parameter_types! {
@@ -108,12 +119,10 @@ pub type SUT = Module<Test>;
 pub fn new_test_ext() -> sp_io::TestExternalities {
     let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
     crate::fees::GenesisConfig::<Test> {
+        destinations: vec![],
         initial_fees: vec![(
             // anchoring state rent fee per day
-            H256::from(&[
-                17, 218, 109, 31, 118, 29, 223, 155, 219, 76, 157, 110, 83, 3, 235, 212, 31, 97,
-                133, 141, 10, 86, 71, 161, 167, 191, 224, 137, 191, 146, 27, 233,
-            ]),
+            FeeKey::AnchorRentPerDay,
             // state rent 0 for tests
             0,
         )],
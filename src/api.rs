@@ -1,10 +1,16 @@
+use codec::Decode;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
-use node_primitives::{BlockNumber, Hash};
+use node_primitives::{AccountId, Balance, BlockNumber, Hash};
 use node_runtime::anchor::AnchorData;
+use node_runtime::fees::{Breakdown, Charge, FeeKey};
+use node_runtime::{FeeSchedule, RelayerSet, ResourceInfo};
 pub use node_runtime::AnchorApi as AnchorRuntimeApi;
+pub use node_runtime::BridgeApi as BridgeRuntimeApi;
+pub use node_runtime::ProtocolFeeApi as ProtocolFeeRuntimeApi;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H160};
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use std::sync::Arc;
 
@@ -51,3 +57,164 @@ where
             })
     }
 }
+
+#[rpc(server)]
+pub trait ProtocolFeeApi {
+    /// Returns the protocol fees a SCALE encoded call is charged on top of the transaction fee
+    #[rpc(name = "fees_quote")]
+    fn quote(&self, encoded_call: Bytes) -> Result<Breakdown<Balance>>;
+}
+
+/// A struct that implements the [`ProtocolFeeApi`].
+pub struct ProtocolFee<C, P> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> ProtocolFee<C, P> {
+    /// Create new `ProtocolFee` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        ProtocolFee {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> ProtocolFeeApi for ProtocolFee<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: ProtocolFeeRuntimeApi<Block>,
+{
+    fn quote(&self, encoded_call: Bytes) -> Result<Breakdown<Balance>> {
+        let call = node_runtime::Call::decode(&mut &*encoded_call).map_err(|e| jsonrpc_core::Error {
+            code: jsonrpc_core::ErrorCode::InvalidParams,
+            message: "Unable to decode call".into(),
+            data: Some(format!("{:?}", e).into()),
+        })?;
+
+        let api = self.client.runtime_api();
+        let best = self.client.info().best_hash;
+        let at = BlockId::hash(best);
+        api.quote(&at, call).map_err(|e| jsonrpc_core::Error {
+            code: jsonrpc_core::ErrorCode::InternalError,
+            message: "Unable to quote protocol fees".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+}
+
+#[rpc(server)]
+pub trait BridgeApi {
+    /// Returns the ids of all whitelisted chains
+    #[rpc(name = "bridge_chains")]
+    fn chains(&self) -> Result<Vec<u8>>;
+
+    /// Returns the relayers and the number of votes required to execute a proposal
+    #[rpc(name = "bridge_relayers")]
+    fn relayers(&self) -> Result<RelayerSet<AccountId>>;
+
+    /// Returns every resource of the bridge and what it is mapped to
+    #[rpc(name = "bridge_resources")]
+    fn resources(&self) -> Result<Vec<ResourceInfo>>;
+
+    /// Returns the default fees of transfers and every fee set per chain and resource
+    #[rpc(name = "bridge_fees")]
+    fn fees(&self) -> Result<FeeSchedule<Balance>>;
+
+    /// Returns the fee of transferring the amount of the native token to the chain
+    #[rpc(name = "bridge_quoteNative")]
+    fn quote_native(&self, dest_id: u8, amount: Balance) -> Result<Breakdown<Balance>>;
+
+    /// Returns the fee of transferring an nft of the registry to the chain
+    #[rpc(name = "bridge_quoteNft")]
+    fn quote_nft(&self, registry_id: H160, dest_id: u8) -> Result<Breakdown<Balance>>;
+
+    /// Returns the fee of transferring the fungible asset to the chain
+    #[rpc(name = "bridge_quoteFungible")]
+    fn quote_fungible(&self, asset_id: u32, dest_id: u8) -> Result<Breakdown<Balance>>;
+}
+
+/// A struct that implements the [`BridgeApi`].
+pub struct Bridge<C, P> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Bridge<C, P> {
+    /// Create new `Bridge` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Bridge {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> Bridge<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: BridgeRuntimeApi<Block>,
+{
+    /// Calls the runtime api at the best block
+    fn call<R, E: std::fmt::Debug>(
+        &self,
+        f: impl FnOnce(&C::Api, &BlockId<Block>) -> std::result::Result<R, E>,
+    ) -> Result<R> {
+        let api = self.client.runtime_api();
+        let best = self.client.info().best_hash;
+        let at = BlockId::hash(best);
+        f(&*api, &at).map_err(|e| jsonrpc_core::Error {
+            code: jsonrpc_core::ErrorCode::InternalError,
+            message: "Unable to query bridge".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+
+    /// Wraps the fee quoted by the runtime api into a breakdown of the key
+    fn quote<E: std::fmt::Debug>(
+        &self,
+        key: FeeKey,
+        f: impl FnOnce(&C::Api, &BlockId<Block>) -> std::result::Result<Option<Balance>, E>,
+    ) -> Result<Breakdown<Balance>> {
+        let amount = self.call(f)?;
+        Ok(Breakdown::new(amount.map(|amount| Charge { key, amount }).into_iter().collect()))
+    }
+}
+
+impl<C, Block> BridgeApi for Bridge<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: BridgeRuntimeApi<Block>,
+{
+    fn chains(&self) -> Result<Vec<u8>> {
+        self.call(|api, at| api.chains(at))
+    }
+
+    fn relayers(&self) -> Result<RelayerSet<AccountId>> {
+        self.call(|api, at| api.relayers(at))
+    }
+
+    fn resources(&self) -> Result<Vec<ResourceInfo>> {
+        self.call(|api, at| api.resources(at))
+    }
+
+    fn fees(&self) -> Result<FeeSchedule<Balance>> {
+        self.call(|api, at| api.fees(at))
+    }
+
+    fn quote_native(&self, dest_id: u8, amount: Balance) -> Result<Breakdown<Balance>> {
+        self.quote(FeeKey::NativeBridge, |api, at| api.quote_native(at, dest_id, amount))
+    }
+
+    fn quote_nft(&self, registry_id: H160, dest_id: u8) -> Result<Breakdown<Balance>> {
+        self.quote(FeeKey::NftBridge, |api, at| api.quote_nft(at, registry_id, dest_id))
+    }
+
+    fn quote_fungible(&self, asset_id: u32, dest_id: u8) -> Result<Breakdown<Balance>> {
+        self.quote(FeeKey::FungibleBridge, |api, at| api.quote_fungible(at, asset_id, dest_id))
+    }
+}
@@ -7,6 +7,7 @@ use node_runtime::{
 	StakerStatus, StakingConfig, SystemConfig, wasm_binary_unwrap,
 };
 use node_runtime::Block;
+use node_runtime::fees::{FeeKey, FeeSplit};
 use node_runtime::constants::currency::*;
 use sc_service;
 use hex_literal::hex;
@@ -16,7 +17,7 @@ use pallet_im_online::sr25519::{AuthorityId as ImOnlineId};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_runtime::{Perbill, traits::{Verify, IdentifyAccount}};
 
-pub use node_primitives::{AccountId, Balance, Hash, Signature};
+pub use node_primitives::{AccountId, Balance, Signature};
 pub use node_runtime::GenesisConfig;
 use sc_service::ChainType;
 
@@ -252,20 +253,21 @@ pub fn testnet_genesis(
 			threshold: 1,
 		}),
         fees: Some(FeesConfig {
-            initial_fees: vec![(
-                // Anchoring state rent fee per day
-                // pre-image: 0xdb4faa73ca6d2016e53c7156087c176b79b169c409b8a0063a07964f3187f9e9
-                // hash   : 0x11da6d1f761ddf9bdb4c9d6e5303ebd41f61858d0a5647a1a7bfe089bf921be9
-                Hash::from(&[
-                    17, 218, 109, 31, 118, 29, 223, 155, 219, 76, 157, 110, 83, 3, 235, 212, 31,
-                    97, 133, 141, 10, 86, 71, 161, 167, 191, 224, 137, 191, 146, 27, 233,
-                ]),
-                // Daily state rent, defined such that it will amount to 0.00259.. CFG (2_590_000_000_000_040) over
-                // 3 years, which is the expected average anchor duration. The other fee components for anchors amount
-                // to about 0.00041.. CFG (410_000_000_000_000), such that the total anchor price for 3 years will be
-                // 0.003.. CFG
-                2_365_296_803_653,
-            )],
+            initial_fees: vec![
+                (
+                    FeeKey::AnchorRentPerDay,
+                    // Daily state rent, defined such that it will amount to 0.00259.. CFG (2_590_000_000_000_040) over
+                    // 3 years, which is the expected average anchor duration. The other fee components for anchors amount
+                    // to about 0.00041.. CFG (410_000_000_000_000), such that the total anchor price for 3 years will be
+                    // 0.003.. CFG
+                    2_365_296_803_653,
+                ),
+                (FeeKey::NftValidate, 10 * CFG),
+                (FeeKey::NftBridge, 20 * CFG),
+                (FeeKey::NativeBridge, 2000 * CFG),
+                (FeeKey::FungibleBridge, 2000 * CFG),
+            ],
+            destinations: vec![(FeeKey::AnchorRentPerDay, FeeSplit::to_author())],
         }),
 		pallet_vesting: Some(Default::default()),
     }
@@ -47,8 +47,8 @@ use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_consensus::SelectChain;
 use sp_consensus_babe::BabeApi;
 use sp_transaction_pool::TransactionPool;
-use crate::api::{AnchorApi, Anchor};
-pub use node_runtime::AnchorApi as AnchorRuntimeApi;
+use crate::api::{AnchorApi, Anchor, BridgeApi, Bridge, ProtocolFeeApi, ProtocolFee};
+pub use node_runtime::{AnchorApi as AnchorRuntimeApi, BridgeApi as BridgeRuntimeApi, ProtocolFeeApi as ProtocolFeeRuntimeApi};
 
 /// Light client extra dependencies.
 pub struct LightDeps<C, F, P> {
@@ -115,6 +115,8 @@ pub fn create_full<C, P, SC>(
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
     C::Api: AnchorRuntimeApi<Block>,
+    C::Api: ProtocolFeeRuntimeApi<Block>,
+    C::Api: BridgeRuntimeApi<Block>,
     P: TransactionPool + 'static,
     SC: SelectChain<Block> +'static,
 {
@@ -175,6 +177,12 @@ pub fn create_full<C, P, SC>(
     io.extend_with(
         AnchorApi::to_delegate(Anchor::new(client.clone()))
     );
+    io.extend_with(
+        ProtocolFeeApi::to_delegate(ProtocolFee::new(client.clone()))
+    );
+    io.extend_with(
+        BridgeApi::to_delegate(Bridge::new(client.clone()))
+    );
 
     io
 }
@@ -0,0 +1,314 @@
+//! Builds the merkle trees of reward claims that `rad_claims` verifies on chain.
+//!
+//! A leaf is the hash of the encoded account followed by the encoded cumulative amount, and a
+//! node is the hash of its two children in ascending order, like `rad_claims::sorted_hash_of`.
+//! A node without a sibling is moved up to the next level as is.
+
+use codec::Encode;
+use serde::Serialize;
+use sp_core::{
+    crypto::{AccountId32, Ss58Codec},
+    hashing::blake2_256,
+    H256,
+};
+use std::{collections::BTreeSet, convert::TryInto, fmt};
+
+#[cfg(test)]
+mod vectors;
+
+/// Balance of the runtime.
+pub type Balance = u128;
+
+/// Maximum number of sorted hashes of a proof that `rad_claims` verifies.
+const MAX_PROOF_LENGTH: u32 = 30;
+
+/// Cumulative amount of rewards of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub account: AccountId32,
+    pub amount: Balance,
+}
+
+/// A claim with the sorted hashes proving it against the root hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProvenClaim {
+    pub account: AccountId32,
+    #[serde(serialize_with = "serialize_as_string")]
+    pub amount: Balance,
+    pub proof: Vec<H256>,
+}
+
+/// Merkle tree of claims, with the total the uploader commits to when uploading the root hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Tree {
+    pub root: H256,
+    #[serde(serialize_with = "serialize_as_string")]
+    pub total: Balance,
+    pub claims: Vec<ProvenClaim>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// No claims to build a tree of
+    NoClaims,
+    /// More claims than proofs of `rad_claims` can prove
+    TooManyClaims,
+    /// An account has more than one claim
+    DuplicateAccount(AccountId32),
+    /// The total of the claims overflows the balance
+    TotalOverflow,
+    /// A line of the CSV is not a claim
+    InvalidLine(usize, String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NoClaims => write!(f, "no claims"),
+            Error::TooManyClaims => write!(f, "more than 2^{} claims", MAX_PROOF_LENGTH),
+            Error::DuplicateAccount(account) => write!(f, "duplicate claim of {}", account),
+            Error::TotalOverflow => write!(f, "total of the claims overflows"),
+            Error::InvalidLine(line, reason) => write!(f, "line {}: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Hash of the leaf of the claim, like the leaf hash of `rad_claims::verify_proofs`.
+pub fn leaf_hash_of(claim: &Claim) -> H256 {
+    let mut v: Vec<u8> = claim.account.encode();
+    v.extend(claim.amount.encode());
+    blake2_256(&v).into()
+}
+
+/// Hash a:b if a < b, else b:a, like `rad_claims::sorted_hash_of`.
+pub fn sorted_hash_of(a: &H256, b: &H256) -> H256 {
+    let mut h: Vec<u8> = Vec::with_capacity(64);
+    if a < b {
+        h.extend_from_slice(a.as_ref());
+        h.extend_from_slice(b.as_ref());
+    } else {
+        h.extend_from_slice(b.as_ref());
+        h.extend_from_slice(a.as_ref());
+    }
+
+    blake2_256(&h).into()
+}
+
+/// Root hash the proof of the claim leads to.
+pub fn root_hash_of(claim: &Claim, proof: &[H256]) -> H256 {
+    proof
+        .iter()
+        .fold(leaf_hash_of(claim), |acc, hash| sorted_hash_of(&acc, hash))
+}
+
+/// Builds the tree of the claims, in the given order.
+pub fn build(claims: Vec<Claim>) -> Result<Tree, Error> {
+    if claims.is_empty() {
+        return Err(Error::NoClaims);
+    }
+    if claims.len() > 1 << MAX_PROOF_LENGTH {
+        return Err(Error::TooManyClaims);
+    }
+
+    let mut accounts = BTreeSet::new();
+    for claim in &claims {
+        if !accounts.insert(&claim.account) {
+            return Err(Error::DuplicateAccount(claim.account.clone()));
+        }
+    }
+    let total = claims
+        .iter()
+        .try_fold(0 as Balance, |total, claim| total.checked_add(claim.amount))
+        .ok_or(Error::TotalOverflow)?;
+
+    let mut proofs = vec![Vec::new(); claims.len()];
+    let mut positions: Vec<usize> = (0..claims.len()).collect();
+    let mut level: Vec<H256> = claims.iter().map(leaf_hash_of).collect();
+    while level.len() > 1 {
+        for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+            if let Some(sibling) = level.get(*position ^ 1) {
+                proof.push(*sibling);
+            }
+            *position /= 2;
+        }
+
+        level = level
+            .chunks(2)
+            .map(|nodes| match nodes {
+                [a, b] => sorted_hash_of(a, b),
+                [a] => *a,
+                _ => unreachable!("chunks are of one or two nodes"),
+            })
+            .collect();
+    }
+
+    Ok(Tree {
+        root: level[0],
+        total,
+        claims: claims
+            .into_iter()
+            .zip(proofs)
+            .map(|(claim, proof)| ProvenClaim {
+                account: claim.account,
+                amount: claim.amount,
+                proof,
+            })
+            .collect(),
+    })
+}
+
+/// Parses a CSV with a line of `account,amount` per claim. Accounts are SS58 or 0x-prefixed hex
+/// encoded, amounts are cumulative and in the smallest unit. An `account,amount` header, empty
+/// lines and lines starting with `#` are skipped.
+pub fn parse_csv(input: &str) -> Result<Vec<Claim>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .filter(|(number, line)| !(*number == 1 && line.eq_ignore_ascii_case("account,amount")))
+        .map(|(number, line)| parse_claim(line).map_err(|reason| Error::InvalidLine(number, reason)))
+        .collect()
+}
+
+fn parse_claim(line: &str) -> Result<Claim, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    match fields[..] {
+        [account, amount] => Ok(Claim {
+            account: parse_account(account)?,
+            amount: amount
+                .parse()
+                .map_err(|e| format!("invalid amount {}: {}", amount, e))?,
+        }),
+        _ => Err(format!("expected 2 fields, found {}", fields.len())),
+    }
+}
+
+fn parse_account(account: &str) -> Result<AccountId32, String> {
+    if account.starts_with("0x") {
+        let bytes: [u8; 32] = sp_core::bytes::from_hex(account)
+            .map_err(|e| format!("invalid account {}: {}", account, e))?
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("invalid account {}: expected 32 bytes", account))?;
+        Ok(bytes.into())
+    } else {
+        AccountId32::from_ss58check(account)
+            .map_err(|e| format!("invalid account {}: {:?}", account, e))
+    }
+}
+
+fn serialize_as_string<S: serde::Serializer, T: fmt::Display>(
+    t: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&t.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector_claims() -> Vec<Claim> {
+        vectors::CLAIMS
+            .iter()
+            .map(|(account, amount)| Claim {
+                account: (*account).into(),
+                amount: *amount,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn build_matches_vectors() {
+        let tree = build(vector_claims()).unwrap();
+
+        assert_eq!(tree.root, vectors::ROOT.into());
+        assert_eq!(tree.total, vectors::CLAIMS.iter().map(|(_, amount)| amount).sum());
+        for (claim, proof) in tree.claims.iter().zip(vectors::PROOFS) {
+            let expected: Vec<H256> = proof.iter().map(|hash| (*hash).into()).collect();
+            assert_eq!(claim.proof, expected);
+        }
+    }
+
+    #[test]
+    fn proofs_lead_to_root() {
+        for n in 1..=17 {
+            let claims: Vec<Claim> = (0..n)
+                .map(|i| Claim {
+                    account: [i as u8; 32].into(),
+                    amount: i as Balance * 1_000,
+                })
+                .collect();
+            let tree = build(claims.clone()).unwrap();
+
+            for (claim, proven) in claims.iter().zip(&tree.claims) {
+                assert_eq!(root_hash_of(claim, &proven.proof), tree.root);
+            }
+        }
+    }
+
+    #[test]
+    fn single_claim_is_root() {
+        let claim = vector_claims().remove(0);
+        let tree = build(vec![claim.clone()]).unwrap();
+
+        assert_eq!(tree.root, leaf_hash_of(&claim));
+        assert_eq!(tree.claims[0].proof, vec![]);
+    }
+
+    #[test]
+    fn build_rejects_invalid_claims() {
+        assert_eq!(build(vec![]), Err(Error::NoClaims));
+
+        let claim = vector_claims().remove(0);
+        assert_eq!(
+            build(vec![claim.clone(), claim.clone()]),
+            Err(Error::DuplicateAccount(claim.account.clone()))
+        );
+
+        let other = Claim {
+            account: [0; 32].into(),
+            amount: Balance::max_value(),
+        };
+        assert_eq!(build(vec![claim, other]), Err(Error::TotalOverflow));
+    }
+
+    #[test]
+    fn parse_csv_accounts() {
+        let csv = "account,amount\n\
+            5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY, 100\n\
+            \n\
+            # bob\n\
+            0x8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48,5\n";
+
+        assert_eq!(
+            parse_csv(csv),
+            Ok(vec![
+                Claim {
+                    account: vectors::CLAIMS[0].0.into(),
+                    amount: 100,
+                },
+                Claim {
+                    account: vectors::CLAIMS[1].0.into(),
+                    amount: 5,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_csv_errors() {
+        assert!(matches!(parse_csv("0x00,1"), Err(Error::InvalidLine(1, _))));
+        assert!(matches!(
+            parse_csv("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY,-1"),
+            Err(Error::InvalidLine(1, _))
+        ));
+        assert!(matches!(
+            parse_csv("account,amount\n5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            Err(Error::InvalidLine(2, _))
+        ));
+    }
+}
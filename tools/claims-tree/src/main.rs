@@ -0,0 +1,37 @@
+use std::{fs, path::PathBuf, process};
+use structopt::StructOpt;
+
+/// Builds the merkle tree of reward claims from a CSV of accounts and cumulative amounts, and
+/// prints its root hash, total and the proof of every claim as JSON.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "claims-tree")]
+struct Opt {
+    /// CSV with a line of `account,amount` per claim. Accounts are SS58 or 0x-prefixed hex
+    /// encoded, amounts are cumulative and in the smallest unit.
+    #[structopt(parse(from_os_str))]
+    input: PathBuf,
+
+    /// File to write the JSON to instead of stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+fn run(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let input = fs::read_to_string(&opt.input)?;
+    let tree = claims_tree::build(claims_tree::parse_csv(&input)?)?;
+    let json = serde_json::to_string_pretty(&tree)?;
+
+    match opt.output {
+        Some(output) => fs::write(output, json)?,
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Opt::from_args()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
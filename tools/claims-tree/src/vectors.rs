@@ -0,0 +1,44 @@
+// Test vectors of a reward claims tree, shared by the tree builder and `rad_claims` to make sure
+// trees built offline are verified on chain. Accounts are the raw bytes of `AccountId32` and
+// amounts are cumulative `u128` balances.
+
+use hex_literal::hex;
+
+/// Claims of the tree, as (account, cumulative amount).
+pub const CLAIMS: &[([u8; 32], u128)] = &[
+    (hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"), 100000000000000000000),
+    (hex!("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48"), 5000000000000000000),
+    (hex!("90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22"), 2500000000000000000001),
+    (hex!("306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20"), 42000000000000000000),
+    (hex!("e659a7a1628cdd93febc04a4e0646ea20e9f5f0ce097d9a05290d4a9e054df4e"), 1),
+];
+
+/// Root hash of the tree.
+pub const ROOT: [u8; 32] = hex!("3553f5c27ea5ac525d5571d18c975641b9ccb803023ea92e2995f99138b69837");
+
+/// Sorted hashes proving every claim, in the order of the claims.
+pub const PROOFS: &[&[[u8; 32]]] = &[
+    &[
+        hex!("c08dbcaf75211d0631c1ca2fe164307ccceaa62a0cb6a37623f82b4549b28504"),
+        hex!("74022ba6c27c8a83acccf94b1c7390a13c2eeb8db746aa553fe621f8179b20fd"),
+        hex!("ceebc21752686d1cc1778750642c4b1def22d3cecaaae5444a48dfd3bbb9c974"),
+    ],
+    &[
+        hex!("5c6f72dfda7a26cbd95ddfe1c6cf781ff99142031d27b80361146798003f9e65"),
+        hex!("74022ba6c27c8a83acccf94b1c7390a13c2eeb8db746aa553fe621f8179b20fd"),
+        hex!("ceebc21752686d1cc1778750642c4b1def22d3cecaaae5444a48dfd3bbb9c974"),
+    ],
+    &[
+        hex!("e8f6d8a5e94f98b47c449a320cba54b68e3e0d4ed1fb4e6598a1c2d5f3a442d7"),
+        hex!("776b512be5026d5dfa4279b84611b81c3f16e13a304e6c2a531087ae7d1f8251"),
+        hex!("ceebc21752686d1cc1778750642c4b1def22d3cecaaae5444a48dfd3bbb9c974"),
+    ],
+    &[
+        hex!("5815e05bc44ad9552b02e21d24395923418fac89e240c0dccf64dc701f854bc5"),
+        hex!("776b512be5026d5dfa4279b84611b81c3f16e13a304e6c2a531087ae7d1f8251"),
+        hex!("ceebc21752686d1cc1778750642c4b1def22d3cecaaae5444a48dfd3bbb9c974"),
+    ],
+    &[
+        hex!("b126be42ac37f650d75c35fbbaf442e2df8d329953efd8f3abe78bd7bd1ff818"),
+    ],
+];